@@ -18,8 +18,14 @@ use room::Event;
 use settings::Settings;
 use std::sync::Arc;
 
-pub use participant::ParticipantLocation;
-pub use room::Room;
+pub use participant::{
+    ConnectionQuality, LocationKind, ParticipantLocation, ResolvedLocation, VideoQuality,
+    ViewAnchor,
+};
+pub use room::{
+    CallDecision, CallResponseSummary, ConnectionSummary, LeaveConfirmation, PendingLeave, Room,
+    RoomCounts, SessionSummary,
+};
 
 struct GlobalActiveCall(Model<ActiveCall>);
 
@@ -69,6 +75,7 @@ pub struct IncomingCall {
     pub calling_user: Arc<User>,
     pub participants: Vec<Arc<User>>,
     pub initial_project: Option<proto::ParticipantProject>,
+    pub context: Option<String>,
 }
 
 /// Singleton global maintaining the user's participation in a room across workspaces.
@@ -101,6 +108,7 @@ impl ActiveCall {
             _subscriptions: vec![
                 client.add_request_handler(cx.weak_model(), Self::handle_incoming_call),
                 client.add_message_handler(cx.weak_model(), Self::handle_call_canceled),
+                client.add_message_handler(cx.weak_model(), Self::handle_session_superseded),
             ],
             client,
             user_store,
@@ -130,6 +138,7 @@ impl ActiveCall {
                 })?
                 .await?,
             initial_project: envelope.payload.initial_project,
+            context: envelope.payload.context,
         };
         this.update(&mut cx, |this, _| {
             *this.incoming_call.0.borrow_mut() = Some(call);
@@ -155,6 +164,20 @@ impl ActiveCall {
         Ok(())
     }
 
+    async fn handle_session_superseded(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SessionSuperseded>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let room = this.update(&mut cx, |this, _| this.room.as_ref().map(|(room, _)| room.clone()))?;
+        if let Some(room) = room {
+            room.update(&mut cx, |room, cx| {
+                room.handle_session_superseded(envelope.payload.reason, cx)
+            })?;
+        }
+        Ok(())
+    }
+
     pub fn global(cx: &AppContext) -> Model<Self> {
         cx.global::<GlobalActiveCall>().0.clone()
     }
@@ -169,6 +192,18 @@ impl ActiveCall {
         called_user_id: u64,
         initial_project: Option<Model<Project>>,
         cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.invite_with_context(called_user_id, initial_project, None, cx)
+    }
+
+    /// Like `invite`, but lets the caller attach a short message (e.g. "wants to pair on
+    /// the auth bug") that is surfaced to the callee alongside the incoming call.
+    pub fn invite_with_context(
+        &mut self,
+        called_user_id: u64,
+        initial_project: Option<Model<Project>>,
+        context: Option<String>,
+        cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
         if !self.pending_invites.insert(called_user_id) {
             return Task::ready(Err(anyhow!("user was already invited")));
@@ -199,7 +234,7 @@ impl ActiveCall {
                 };
 
                 room.update(&mut cx, move |room, cx| {
-                    room.call(called_user_id, initial_project_id, cx)
+                    room.call_with_context(called_user_id, initial_project_id, context, cx)
                 })?
                 .await?;
 
@@ -216,6 +251,7 @@ impl ActiveCall {
                                 Room::create(
                                     called_user_id,
                                     initial_project,
+                                    context,
                                     client,
                                     user_store,
                                     cx,
@@ -318,6 +354,45 @@ impl ActiveCall {
         })
     }
 
+    /// Like [`Self::accept_incoming`], but renders `prefetched_room` immediately instead of
+    /// waiting on the `JoinRoom` round-trip. See [`Room::join_with_prefetched_room`].
+    pub fn accept_incoming_with_prefetched_room(
+        &mut self,
+        prefetched_room: proto::Room,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if self.room.is_some() {
+            return Task::ready(Err(anyhow!("cannot join while on another call")));
+        }
+
+        let call = if let Some(call) = self.incoming_call.0.borrow_mut().take() {
+            call
+        } else {
+            return Task::ready(Err(anyhow!("no incoming call")));
+        };
+
+        if self.pending_room_creation.is_some() {
+            return Task::ready(Ok(()));
+        }
+
+        let room_id = call.room_id;
+        let client = self.client.clone();
+        let user_store = self.user_store.clone();
+        let join = self._join_debouncer.spawn(cx, move |cx| {
+            Room::join_with_prefetched_room(room_id, Some(prefetched_room), client, user_store, cx)
+        });
+
+        cx.spawn(|this, mut cx| async move {
+            let room = join.await?;
+            this.update(&mut cx, |this, cx| this.set_room(room.clone(), cx))?
+                .await?;
+            this.update(&mut cx, |this, cx| {
+                this.report_call_event("accept incoming", cx)
+            })?;
+            Ok(())
+        })
+    }
+
     pub fn decline_incoming(&mut self, _: &mut ModelContext<Self>) -> Result<()> {
         let call = self
             .incoming_call
@@ -366,7 +441,17 @@ impl ActiveCall {
         })
     }
 
-    pub fn hang_up(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+    pub fn hang_up(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<SessionSummary>> {
+        self.hang_up_with_message(None, cx)
+    }
+
+    /// Like `hang_up`, but lets the caller attach a short farewell that's broadcast to the
+    /// other participants still in the room.
+    pub fn hang_up_with_message(
+        &mut self,
+        message: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<SessionSummary>> {
         cx.notify();
         self.report_call_event("hang up", cx);
 
@@ -375,9 +460,9 @@ impl ActiveCall {
         let channel_id = self.channel_id(cx);
         if let Some((room, _)) = self.room.take() {
             cx.emit(Event::RoomLeft { channel_id });
-            room.update(cx, |room, cx| room.leave(cx))
+            room.update(cx, |room, cx| room.leave_with_message(message, cx))
         } else {
-            Task::ready(Ok(()))
+            Task::ready(Ok(SessionSummary::default()))
         }
     }
 
@@ -419,7 +504,10 @@ impl ActiveCall {
         if project.is_some() || !*ZED_ALWAYS_ACTIVE {
             self.location = project.map(|project| project.downgrade());
             if let Some((room, _)) = self.room.as_ref() {
-                return room.update(cx, |room, cx| room.set_location(project, cx));
+                let set_location = room.update(cx, |room, cx| room.set_location(project, cx));
+                return cx
+                    .background_executor()
+                    .spawn(async move { set_location.await.map(|_| ()) });
             }
         }
         Task::ready(Ok(()))
@@ -456,7 +544,10 @@ impl ActiveCall {
                         .and_then(|location| location.upgrade());
                     let channel_id = room.read(cx).channel_id();
                     cx.emit(Event::RoomJoined { channel_id });
-                    room.update(cx, |room, cx| room.set_location(location.as_ref(), cx))
+                    let set_location =
+                        room.update(cx, |room, cx| room.set_location(location.as_ref(), cx));
+                    cx.background_executor()
+                        .spawn(async move { set_location.await.map(|_| ()) })
                 }
             } else {
                 self.room = None;
@@ -1,12 +1,124 @@
 use anyhow::{anyhow, Result};
+use bitflags::bitflags;
 use client::ParticipantIndex;
 use client::{proto, User};
 use collections::HashMap;
-use gpui::WeakModel;
+use gpui::{Model, WeakModel};
 pub use live_kit_client::Frame;
 pub use live_kit_client::{RemoteAudioTrack, RemoteVideoTrack};
-use project::Project;
-use std::sync::Arc;
+use project::{Project, ProjectPath};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// How long after [`RemoteParticipant::note_activity`] a participant keeps showing as "active",
+/// e.g. for a lightweight "editing now" indicator. Coarser-grained than [`Presence`], which
+/// tracks connectivity over many seconds rather than moment-to-moment editing.
+pub const ACTIVITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The platform a remote participant is connecting from, used to power indicators like
+/// "Bob is on mobile". Populated from `Participant::platform`; unset or unrecognized values
+/// fall back to `Unknown` rather than failing the whole room update.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum ClientKind {
+    #[default]
+    Unknown,
+    MacOs,
+    Linux,
+    Windows,
+    Web,
+    Ios,
+    Android,
+}
+
+impl ClientKind {
+    pub fn from_platform_str(platform: Option<&str>) -> Self {
+        match platform {
+            Some("macos") => Self::MacOs,
+            Some("linux") => Self::Linux,
+            Some("windows") => Self::Windows,
+            Some("web") => Self::Web,
+            Some("ios") => Self::Ios,
+            Some("android") => Self::Android,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Coarse connection-quality bucket for a remote participant, for a session-health widget.
+/// Currently always [`ConnectionQuality::Unknown`]: LiveKit's per-connection quality signal
+/// isn't wired up to [`RemoteParticipant`] yet, so there's nothing to bucket. This exists so
+/// that plumbing has a stable type and call site to land in once it is.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum ConnectionQuality {
+    #[default]
+    Unknown,
+    Good,
+    Fair,
+    Poor,
+}
+
+bitflags! {
+    /// Features a remote participant's client may or may not support, for hiding UI affordances
+    /// (e.g. "request screen share") that peer couldn't honor. There's no dedicated wire field
+    /// for this yet, so [`RemoteParticipant::capabilities`] is inferred from [`ClientKind`] as a
+    /// placeholder until the server actually negotiates and sends a real capability set - see
+    /// [`Capabilities::from_client_kind`].
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+    pub struct Capabilities: u32 {
+        const SCREEN_SHARE = 1 << 0;
+        const VIDEO = 1 << 1;
+    }
+}
+
+impl Capabilities {
+    /// Best-effort capability set for a client, based only on its platform. Browser-based (`Web`)
+    /// clients can't capture the screen through LiveKit today, so `SCREEN_SHARE` is withheld for
+    /// them; every other recognized platform is assumed to support everything.
+    pub fn from_client_kind(client_kind: ClientKind) -> Self {
+        match client_kind {
+            ClientKind::Web => Capabilities::VIDEO,
+            _ => Capabilities::all(),
+        }
+    }
+}
+
+/// Whether a shared project can be edited by collaborators other than its host. Carried on the
+/// wire as [`proto::ParticipantProject::read_only`]; see [`crate::room::Room::project_access`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProjectAccess {
+    ReadWrite,
+    ReadOnly,
+}
+
+impl ProjectAccess {
+    pub fn from_read_only(read_only: bool) -> Self {
+        if read_only {
+            Self::ReadOnly
+        } else {
+            Self::ReadWrite
+        }
+    }
+}
+
+/// Whether a remote participant's heartbeat is recent enough to trust. See
+/// [`crate::room::Room::refresh_presence`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum Presence {
+    #[default]
+    Active,
+    Away,
+}
+
+/// The resolution requested for a remote participant's video via
+/// [`crate::room::Room::request_video_quality`] - lower for a thumbnail, full once they're
+/// focused.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VideoQuality {
+    Low,
+    Full,
+}
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ParticipantLocation {
@@ -30,13 +142,102 @@ impl ParticipantLocation {
             None => Err(anyhow!("participant location was not provided")),
         }
     }
+
+    /// The coarse category of this location, ignoring which specific project (if any) is
+    /// involved. Used to group participants for display, e.g. in a presence sidebar.
+    pub fn kind(&self) -> LocationKind {
+        match self {
+            Self::SharedProject { .. } => LocationKind::SharedProject,
+            Self::UnsharedProject => LocationKind::PrivateProject,
+            Self::External => LocationKind::External,
+        }
+    }
 }
 
-#[derive(Clone, Default)]
+/// See [`ParticipantLocation::kind`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LocationKind {
+    SharedProject,
+    PrivateProject,
+    External,
+}
+
+/// A cursor/selection position within the buffer a participant has open, broadcast alongside
+/// [`RemoteParticipant::open_path`] so "follow" can land on the exact spot being looked at rather
+/// than just the top of the file. Expressed as plain line/character offsets rather than a buffer
+/// `Anchor`, since `call` doesn't depend on `language`'s buffer machinery and this is advisory -
+/// it may be a little stale by the time a peer follows.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub struct ViewAnchor {
+    pub line: u32,
+    pub character: u32,
+}
+
+impl ViewAnchor {
+    pub fn from_proto(anchor: proto::ViewAnchor) -> Self {
+        Self {
+            line: anchor.line,
+            character: anchor.character,
+        }
+    }
+
+    pub fn to_proto(&self) -> proto::ViewAnchor {
+        proto::ViewAnchor {
+            line: self.line,
+            character: self.character,
+        }
+    }
+}
+
+/// A [`ParticipantLocation`] with its wire-level project id resolved to a local [`Project`]
+/// handle, for callers that want to actually do something with the project (e.g. jump to it)
+/// rather than just display where the participant is.
+#[derive(Clone, Debug)]
+pub enum ResolvedLocation {
+    SharedProject(Model<Project>),
+    /// The participant is in a shared project, but this client doesn't have it open.
+    UnknownProject,
+    UnsharedProject,
+    External,
+}
+
+#[derive(Clone)]
 pub struct LocalParticipant {
     pub projects: Vec<proto::ParticipantProject>,
     pub active_project: Option<WeakModel<Project>>,
     pub role: proto::ChannelRole,
+    pub noise_suppression_enabled: bool,
+    pub echo_cancellation_enabled: bool,
+    pub audio_input_device_id: Option<String>,
+    pub audio_output_device_id: Option<String>,
+    /// Master output volume, as a multiplier against the default - `1.0` is unity gain. See
+    /// [`crate::room::Room::set_output_gain`].
+    pub output_gain: f32,
+    /// Microphone sensitivity, as a multiplier against the default - `1.0` is unity gain. See
+    /// [`crate::room::Room::set_input_gain`].
+    pub input_gain: f32,
+    pub video_enabled: bool,
+    /// Whether the local user joined in a listen-only capacity, with mic/camera/screen
+    /// publishing disabled. See [`crate::room::Room::set_observer_mode`].
+    pub is_observer: bool,
+}
+
+impl Default for LocalParticipant {
+    fn default() -> Self {
+        Self {
+            projects: Default::default(),
+            active_project: None,
+            role: proto::ChannelRole::default(),
+            noise_suppression_enabled: true,
+            echo_cancellation_enabled: true,
+            audio_input_device_id: None,
+            audio_output_device_id: None,
+            output_gain: 1.0,
+            input_gain: 1.0,
+            video_enabled: false,
+            is_observer: false,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -47,8 +248,233 @@ pub struct RemoteParticipant {
     pub projects: Vec<proto::ParticipantProject>,
     pub location: ParticipantLocation,
     pub participant_index: ParticipantIndex,
+    pub client_kind: ClientKind,
+    /// See [`Capabilities`].
+    pub capabilities: Capabilities,
+    pub connection_quality: ConnectionQuality,
+    /// A coarse server-region hint (e.g. `"us-east"`), to help explain latency differences
+    /// between participants. `None` if the server didn't report one. See
+    /// [`crate::room::Room::participants_by_region`].
+    pub region: Option<String>,
+    /// The buffer/file this participant currently has open, if their location is a
+    /// [`ParticipantLocation::SharedProject`] that carried one. Lets "follow" jump straight to
+    /// the file they're looking at, not just the project.
+    pub open_path: Option<ProjectPath>,
+    /// Where in `open_path` this participant's cursor/selection currently is, if their client
+    /// reported one. See [`ViewAnchor`].
+    pub open_anchor: Option<ViewAnchor>,
+    /// Whether this participant joined in a listen-only capacity and has mic/camera/screen
+    /// publishing disabled.
+    pub is_observer: bool,
+    /// Whether this participant's heartbeat is recent (`Active`) or stale (`Away`). Refreshed by
+    /// [`crate::room::Room::refresh_presence`]; defaults to `Active` whenever the participant is
+    /// touched by a room update.
+    pub presence: Presence,
+    pub last_seen: u64,
     pub muted: bool,
     pub speaking: bool,
+    /// Finer-grained than `muted` - distinguishes having no microphone, having denied mic
+    /// permission, and having a mic but choosing to mute it. See
+    /// [`crate::room::Room::participants_without_mic`].
+    pub mic_state: proto::MicState,
+    /// The kind of network connection this participant joined from, for connection-quality
+    /// explainers like "Bob is on cellular". `Unknown` if their client didn't report one.
+    pub network_type: proto::NetworkType,
+    /// The [`crate::room::Room`]-local speech sequence number at which this participant was last
+    /// observed speaking, for sorting by recency. See
+    /// [`crate::room::Room::participants_by_recent_speech`]. `None` if they haven't spoken since
+    /// joining.
+    pub last_spoke_sequence: Option<u64>,
+    pub video_enabled: bool,
     pub video_tracks: HashMap<live_kit_client::Sid, Arc<RemoteVideoTrack>>,
     pub audio_tracks: HashMap<live_kit_client::Sid, Arc<RemoteAudioTrack>>,
+    /// When this participant last sent a [`crate::room::Room::report_activity`] ping, for
+    /// [`Self::is_active`]. `None` if they haven't sent one since joining.
+    pub last_active_at: Option<Instant>,
+}
+
+impl RemoteParticipant {
+    /// Whether this participant is showing as "active" for an "editing now" indicator - i.e.
+    /// they sent an activity ping within [`ACTIVITY_TIMEOUT`]. Decays back to `false` on its
+    /// own as time passes, without needing another signal to flip it off.
+    pub fn is_active(&self) -> bool {
+        self.last_active_at
+            .is_some_and(|last_active_at| last_active_at.elapsed() < ACTIVITY_TIMEOUT)
+    }
+
+    pub(crate) fn note_activity(&mut self) {
+        self.last_active_at = Some(Instant::now());
+    }
+}
+
+impl PartialEq for RemoteParticipant {
+    /// Compares identity and UI-relevant state, not bookkeeping. Excludes `last_seen`,
+    /// `last_spoke_sequence`, and `last_active_at` (internal sequence counters and timestamps)
+    /// and `video_tracks`/`audio_tracks` (native track handles with no meaningful equality), so
+    /// two participants that only differ in those fields still compare equal for diffing
+    /// purposes.
+    fn eq(&self, other: &Self) -> bool {
+        self.peer_id == other.peer_id
+            && self.user.id == other.user.id
+            && self.role == other.role
+            && self.projects == other.projects
+            && self.location == other.location
+            && self.participant_index == other.participant_index
+            && self.client_kind == other.client_kind
+            && self.capabilities == other.capabilities
+            && self.connection_quality == other.connection_quality
+            && self.region == other.region
+            && self.open_path == other.open_path
+            && self.open_anchor == other.open_anchor
+            && self.is_observer == other.is_observer
+            && self.presence == other.presence
+            && self.muted == other.muted
+            && self.speaking == other.speaking
+            && self.mic_state == other.mic_state
+            && self.network_type == other.network_type
+            && self.video_enabled == other.video_enabled
+    }
+}
+
+impl Eq for RemoteParticipant {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Capabilities, ClientKind, ConnectionQuality, ParticipantLocation, Presence,
+        RemoteParticipant, ACTIVITY_TIMEOUT,
+    };
+    use client::{proto, ParticipantIndex, User};
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    fn participant(peer_id: u32) -> RemoteParticipant {
+        RemoteParticipant {
+            user: Arc::new(User::default()),
+            peer_id: proto::PeerId {
+                owner_id: peer_id,
+                id: 0,
+            },
+            role: proto::ChannelRole::Member,
+            projects: Vec::new(),
+            location: ParticipantLocation::External,
+            participant_index: ParticipantIndex(0),
+            client_kind: ClientKind::Unknown,
+            capabilities: Capabilities::all(),
+            connection_quality: ConnectionQuality::Unknown,
+            region: None,
+            open_path: None,
+            open_anchor: None,
+            is_observer: false,
+            presence: Presence::Active,
+            last_seen: 0,
+            muted: false,
+            speaking: false,
+            mic_state: proto::MicState::Active,
+            network_type: proto::NetworkType::Unknown,
+            last_spoke_sequence: None,
+            video_enabled: false,
+            video_tracks: Default::default(),
+            audio_tracks: Default::default(),
+            last_active_at: None,
+        }
+    }
+
+    #[test]
+    fn from_platform_str_maps_known_platforms() {
+        assert_eq!(ClientKind::from_platform_str(Some("macos")), ClientKind::MacOs);
+        assert_eq!(ClientKind::from_platform_str(Some("web")), ClientKind::Web);
+        assert_eq!(
+            ClientKind::from_platform_str(Some("carrier-pigeon")),
+            ClientKind::Unknown
+        );
+        assert_eq!(ClientKind::from_platform_str(None), ClientKind::Unknown);
+    }
+
+    #[test]
+    fn mic_state_maps_from_proto() {
+        let participant_with = |mic_state: Option<proto::MicState>| proto::Participant {
+            mic_state: mic_state.map(|state| state as i32),
+            ..Default::default()
+        };
+
+        assert_eq!(participant_with(None).mic_state(), proto::MicState::Active);
+        assert_eq!(
+            participant_with(Some(proto::MicState::Active)).mic_state(),
+            proto::MicState::Active
+        );
+        assert_eq!(
+            participant_with(Some(proto::MicState::Muted)).mic_state(),
+            proto::MicState::Muted
+        );
+        assert_eq!(
+            participant_with(Some(proto::MicState::Denied)).mic_state(),
+            proto::MicState::Denied
+        );
+        assert_eq!(
+            participant_with(Some(proto::MicState::NoDevice)).mic_state(),
+            proto::MicState::NoDevice
+        );
+    }
+
+    #[test]
+    fn network_type_maps_from_proto() {
+        let participant_with = |network_type: Option<proto::NetworkType>| proto::Participant {
+            network_type: network_type.map(|network_type| network_type as i32),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            participant_with(None).network_type(),
+            proto::NetworkType::Unknown
+        );
+        assert_eq!(
+            participant_with(Some(proto::NetworkType::Wifi)).network_type(),
+            proto::NetworkType::Wifi
+        );
+        assert_eq!(
+            participant_with(Some(proto::NetworkType::Ethernet)).network_type(),
+            proto::NetworkType::Ethernet
+        );
+        assert_eq!(
+            participant_with(Some(proto::NetworkType::Cellular)).network_type(),
+            proto::NetworkType::Cellular
+        );
+    }
+
+    #[test]
+    fn capabilities_from_client_kind_withholds_screen_share_for_web() {
+        assert!(!Capabilities::from_client_kind(ClientKind::Web).contains(Capabilities::SCREEN_SHARE));
+        assert!(Capabilities::from_client_kind(ClientKind::Web).contains(Capabilities::VIDEO));
+        assert!(Capabilities::from_client_kind(ClientKind::MacOs).contains(Capabilities::SCREEN_SHARE));
+    }
+
+    #[test]
+    fn is_active_decays_after_timeout() {
+        let mut p = participant(1);
+        assert!(!p.is_active());
+
+        p.note_activity();
+        assert!(p.is_active());
+
+        p.last_active_at = Instant::now().checked_sub(ACTIVITY_TIMEOUT + Duration::from_secs(1));
+        assert!(!p.is_active());
+    }
+
+    #[test]
+    fn equality_ignores_bookkeeping_fields() {
+        let mut a = participant(1);
+        let mut b = a.clone();
+        b.last_seen = 42;
+        assert_eq!(a, b);
+
+        a.speaking = true;
+        assert_ne!(a, b);
+
+        let mut c = participant(2);
+        c.speaking = true;
+        assert_ne!(a, c);
+    }
 }
@@ -1,6 +1,10 @@
 use crate::{
     call_settings::CallSettings,
-    participant::{LocalParticipant, ParticipantLocation, RemoteParticipant},
+    participant::{
+        Capabilities, ClientKind, ConnectionQuality, LocalParticipant, LocationKind,
+        ParticipantLocation, Presence, ProjectAccess, RemoteParticipant, ResolvedLocation,
+        VideoQuality, ViewAnchor,
+    },
 };
 use anyhow::{anyhow, Result};
 use audio::{Audio, Sound};
@@ -8,21 +12,101 @@ use client::{
     proto::{self, PeerId},
     ChannelId, Client, ParticipantIndex, TypedEnvelope, User, UserStore,
 };
-use collections::{BTreeMap, HashMap, HashSet};
+use collections::{BTreeMap, FxHasher, HashMap, HashSet, VecDeque};
 use fs::Fs;
-use futures::{FutureExt, StreamExt};
+use futures::{
+    channel::{mpsc, oneshot},
+    future::Shared,
+    FutureExt, StreamExt,
+};
 use gpui::{
-    AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task, WeakModel,
+    AppContext, AsyncAppContext, Context, EventEmitter, Hsla, Model, ModelContext, Task,
+    WeakModel,
 };
 use language::LanguageRegistry;
-use live_kit_client::{LocalAudioTrack, LocalTrackPublication, LocalVideoTrack, RoomUpdate};
+use live_kit_client::{
+    LocalAudioTrack, LocalTrackPublication, LocalVideoTrack, RemoteVideoTrack, RoomUpdate,
+};
 use postage::{sink::Sink, stream::Stream, watch};
-use project::Project;
+use project::{Project, ProjectPath};
+use serde_derive::{Deserialize, Serialize};
 use settings::Settings as _;
-use std::{future::Future, mem, sync::Arc, time::Duration};
+use std::{
+    future::Future,
+    hash::{Hash, Hasher},
+    mem,
+    ops::RangeInclusive,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use util::{post_inc, ResultExt, TryFutureExt};
 
 pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+pub const JOIN_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long a pending (ringing) participant can go unanswered before the client gives up on
+/// them locally and cancels their invite, in case the server never gets around to expiring it.
+pub const PENDING_PARTICIPANT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long [`Room::follow`] waits, after a disconnect, for the followed leader to reappear in
+/// the roster before giving up and emitting [`Event::FollowTargetLost`].
+pub const FOLLOW_TARGET_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default for [`Room::set_mass_removal_resync_threshold`].
+pub const DEFAULT_MASS_REMOVAL_RESYNC_THRESHOLD: f64 = 0.5;
+
+/// How long a departed participant's last known location is kept around to optimistically
+/// restore on a quick rejoin (same user, new peer id), before their view resets to blank like
+/// any other fresh join. See [`Room::apply_room_update`].
+pub const RECENT_LOCATION_TTL: Duration = Duration::from_secs(10);
+
+/// The allowed range for [`Room::set_output_gain`]/[`Room::set_input_gain`] - `0.0` mutes,
+/// `1.0` is unity gain, and anything above that is amplification.
+pub const GAIN_RANGE: RangeInclusive<f32> = 0.0..=2.0;
+
+/// How many [`MIC_TEST_SAMPLE_INTERVAL`]-spaced samples an unstopped [`Room::mic_test`] loopback
+/// takes before giving up on its own.
+const MIC_TEST_SAMPLE_COUNT: u32 = 50;
+/// ~5 seconds at [`MIC_TEST_SAMPLE_COUNT`] samples.
+const MIC_TEST_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+/// See [`Room::mic_test`] - there's no real level-metering primitive to sample yet.
+const MIC_TEST_PLACEHOLDER_LEVEL: f32 = 0.0;
+
+#[cfg(any(test, feature = "test-support"))]
+static JOIN_TIMEOUT_OVERRIDE: util::test::TestOverride = util::test::TestOverride::new();
+
+#[cfg(any(test, feature = "test-support"))]
+static FOLLOW_TARGET_TIMEOUT_OVERRIDE: util::test::TestOverride = util::test::TestOverride::new();
+
+fn join_timeout() -> Duration {
+    #[cfg(any(test, feature = "test-support"))]
+    if let Some(millis) = JOIN_TIMEOUT_OVERRIDE.get() {
+        return Duration::from_millis(millis);
+    }
+    JOIN_TIMEOUT
+}
+
+/// Overrides [`JOIN_TIMEOUT`] for the current process, e.g. to make a test's timeout fire
+/// immediately by passing `Duration::ZERO`.
+#[cfg(any(test, feature = "test-support"))]
+pub fn set_join_timeout_for_test(timeout: Duration) {
+    JOIN_TIMEOUT_OVERRIDE.set(timeout.as_millis() as u64);
+}
+
+fn follow_target_timeout() -> Duration {
+    #[cfg(any(test, feature = "test-support"))]
+    if let Some(millis) = FOLLOW_TARGET_TIMEOUT_OVERRIDE.get() {
+        return Duration::from_millis(millis);
+    }
+    FOLLOW_TARGET_TIMEOUT
+}
+
+/// Overrides [`FOLLOW_TARGET_TIMEOUT`] for the current process, e.g. to make a test's timeout
+/// fire immediately by passing `Duration::ZERO`.
+#[cfg(any(test, feature = "test-support"))]
+pub fn set_follow_target_timeout_for_test(timeout: Duration) {
+    FOLLOW_TARGET_TIMEOUT_OVERRIDE.set(timeout.as_millis() as u64);
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Event {
@@ -32,6 +116,17 @@ pub enum Event {
     ParticipantLocationChanged {
         participant_id: proto::PeerId,
     },
+    RoleChanged {
+        peer_id: proto::PeerId,
+        role: proto::ChannelRole,
+    },
+    /// A remote participant entered or left observer mode. Fires only when `is_observer`
+    /// actually flips, same as [`Event::ParticipantLocationChanged`]/[`Event::RoleChanged`] -
+    /// see [`Room::apply_room_update`].
+    ParticipantObserverModeChanged {
+        participant_id: proto::PeerId,
+        is_observer: bool,
+    },
     RemoteVideoTracksChanged {
         participant_id: proto::PeerId,
     },
@@ -46,15 +141,396 @@ pub enum Event {
     RemoteProjectUnshared {
         project_id: u64,
     },
+    /// How many remote participants are in `project_id` changed - someone's location moved into
+    /// or out of it, or they left the room entirely. See [`Room::project_occupancy`].
+    ProjectOccupancyChanged {
+        project_id: u64,
+        count: usize,
+    },
+    FollowTargetLostProject {
+        peer_id: proto::PeerId,
+        project_id: u64,
+    },
+    /// `remote_participants` just went from empty to non-empty. Fires once per such transition -
+    /// not again for subsequent joins until the roster empties out and refills. See
+    /// [`Room::apply_room_update`].
+    FirstParticipantJoined {
+        peer_id: proto::PeerId,
+    },
+    /// A participant was just added to the roster - fires for every join, not just the first.
+    /// While joining a large room, [`Self::apply_initial_roster`] applies the roster in chunks,
+    /// so this fires once per participant as each chunk lands rather than all at once. See
+    /// [`Event::RosterComplete`].
+    ParticipantJoined {
+        peer_id: proto::PeerId,
+    },
+    /// More than one participant joined as part of the same [`Room::apply_room_update`] - e.g. a
+    /// roster chunk landing, or several people arriving in the same server update. Fires
+    /// alongside the individual [`Event::ParticipantJoined`] events, not instead of them, so a
+    /// listener that only cares about one sound per batch can use this while anything that wants
+    /// per-participant notifications keeps using [`Event::ParticipantJoined`].
+    ParticipantsJoinedBatch {
+        peer_ids: Vec<proto::PeerId>,
+    },
+    /// The initial roster has been fully applied - always fires exactly once after a join
+    /// settles, whether or not [`Self::apply_initial_roster`] had to chunk it.
+    RosterComplete,
+    /// The local user started or stopped following someone, or resumed following them after a
+    /// reconnect. `None` means "not following anyone". See [`Room::follow`]/[`Room::unfollow`].
+    FollowingChanged {
+        leader_id: Option<proto::PeerId>,
+    },
+    /// The followed leader's location changed, or they just reappeared after a reconnect - jump
+    /// to wherever they are now. See [`Room::follow`].
+    FollowTargetMoved {
+        leader_id: proto::PeerId,
+    },
+    /// The followed leader didn't return within [`FOLLOW_TARGET_TIMEOUT`] of a disconnect;
+    /// following was canceled locally. See [`Room::expire_follow_target`].
+    FollowTargetLost {
+        leader_id: u64,
+    },
+    ParticipantFarewell {
+        peer_id: proto::PeerId,
+        message: Option<String>,
+        reason: proto::LeaveReason,
+    },
+    /// Someone started following the local user, relayed by the server. See [`Room::followers`].
+    FollowerAdded {
+        follower_id: proto::PeerId,
+    },
+    /// Someone stopped following the local user, relayed by the server. See [`Room::followers`].
+    FollowerRemoved {
+        follower_id: proto::PeerId,
+    },
+    VideoChanged,
+    /// The local user's microphone was muted or unmuted, whether by [`Room::toggle_mute`] or as
+    /// a side effect of deafening. See [`Room::set_mute`].
+    LocalMuteChanged {
+        muted: bool,
+    },
+    RoomFull,
+    RoomHasCapacity,
+    MediaTokenRefreshed,
     RemoteProjectJoined {
         project_id: u64,
     },
     RemoteProjectInvitationDiscarded {
         project_id: u64,
     },
+    /// A pending participant's invite was canceled locally because it went unanswered for too
+    /// long. See [`Room::reschedule_pending_participant_expirations`].
+    PendingParticipantExpired {
+        user_id: u64,
+    },
+    /// The client lost its connection to the server and the room has started trying to
+    /// reconnect. See [`DisconnectDiagnostics`].
+    LocalConnectionLost {
+        diagnostics: DisconnectDiagnostics,
+    },
+    /// This session's membership in the room was taken over by another session of the same
+    /// user, e.g. they joined the same room from another device. See
+    /// [`Room::handle_session_superseded`].
+    SessionSuperseded {
+        reason: String,
+    },
+    /// The host or a co-host asked us to mute, via [`proto::RequestMute`]. Unlike
+    /// [`Room::handle_force_mute`], this doesn't mute anything by itself - it's on the UI to
+    /// show something like "Host asked you to mute" and call [`Room::toggle_mute`] if accepted.
+    MuteRequested {
+        by: proto::PeerId,
+    },
+    Error {
+        message: String,
+    },
     RoomLeft {
         channel_id: Option<ChannelId>,
     },
+    /// The audio/video backend failed to come up, e.g. LiveKit couldn't be reached. The room
+    /// stays joined as text-only collaboration rather than failing outright - see
+    /// [`Room::new`]. The backend isn't re-created afterwards, so `share_screen`/`share_microphone`
+    /// return errors for the rest of the session; `toggle_mute`/`unmute` don't return an error and
+    /// have no track to act on.
+    MediaUnavailable {
+        reason: String,
+    },
+}
+
+impl Event {
+    /// The variant of this event, with its payload stripped off. See [`Room::on_event`].
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Event::RoomJoined { .. } => EventKind::RoomJoined,
+            Event::ParticipantLocationChanged { .. } => EventKind::ParticipantLocationChanged,
+            Event::RoleChanged { .. } => EventKind::RoleChanged,
+            Event::ParticipantObserverModeChanged { .. } => {
+                EventKind::ParticipantObserverModeChanged
+            }
+            Event::RemoteVideoTracksChanged { .. } => EventKind::RemoteVideoTracksChanged,
+            Event::RemoteAudioTracksChanged { .. } => EventKind::RemoteAudioTracksChanged,
+            Event::RemoteProjectShared { .. } => EventKind::RemoteProjectShared,
+            Event::RemoteProjectUnshared { .. } => EventKind::RemoteProjectUnshared,
+            Event::ProjectOccupancyChanged { .. } => EventKind::ProjectOccupancyChanged,
+            Event::FollowTargetLostProject { .. } => EventKind::FollowTargetLostProject,
+            Event::FirstParticipantJoined { .. } => EventKind::FirstParticipantJoined,
+            Event::ParticipantJoined { .. } => EventKind::ParticipantJoined,
+            Event::ParticipantsJoinedBatch { .. } => EventKind::ParticipantsJoinedBatch,
+            Event::RosterComplete => EventKind::RosterComplete,
+            Event::FollowingChanged { .. } => EventKind::FollowingChanged,
+            Event::FollowTargetMoved { .. } => EventKind::FollowTargetMoved,
+            Event::FollowTargetLost { .. } => EventKind::FollowTargetLost,
+            Event::ParticipantFarewell { .. } => EventKind::ParticipantFarewell,
+            Event::FollowerAdded { .. } => EventKind::FollowerAdded,
+            Event::FollowerRemoved { .. } => EventKind::FollowerRemoved,
+            Event::VideoChanged => EventKind::VideoChanged,
+            Event::LocalMuteChanged { .. } => EventKind::LocalMuteChanged,
+            Event::RoomFull => EventKind::RoomFull,
+            Event::RoomHasCapacity => EventKind::RoomHasCapacity,
+            Event::MediaTokenRefreshed => EventKind::MediaTokenRefreshed,
+            Event::RemoteProjectJoined { .. } => EventKind::RemoteProjectJoined,
+            Event::RemoteProjectInvitationDiscarded { .. } => {
+                EventKind::RemoteProjectInvitationDiscarded
+            }
+            Event::PendingParticipantExpired { .. } => EventKind::PendingParticipantExpired,
+            Event::LocalConnectionLost { .. } => EventKind::LocalConnectionLost,
+            Event::SessionSuperseded { .. } => EventKind::SessionSuperseded,
+            Event::MuteRequested { .. } => EventKind::MuteRequested,
+            Event::Error { .. } => EventKind::Error,
+            Event::RoomLeft { .. } => EventKind::RoomLeft,
+            Event::MediaUnavailable { .. } => EventKind::MediaUnavailable,
+        }
+    }
+}
+
+/// Identifies an [`Event`] variant without its payload, for filtering subscriptions via
+/// [`Room::on_event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    RoomJoined,
+    ParticipantLocationChanged,
+    RoleChanged,
+    ParticipantObserverModeChanged,
+    RemoteVideoTracksChanged,
+    RemoteAudioTracksChanged,
+    RemoteProjectShared,
+    RemoteProjectUnshared,
+    ProjectOccupancyChanged,
+    FollowTargetLostProject,
+    FirstParticipantJoined,
+    ParticipantJoined,
+    ParticipantsJoinedBatch,
+    RosterComplete,
+    FollowingChanged,
+    FollowTargetMoved,
+    FollowTargetLost,
+    ParticipantFarewell,
+    FollowerAdded,
+    FollowerRemoved,
+    VideoChanged,
+    LocalMuteChanged,
+    RoomFull,
+    RoomHasCapacity,
+    MediaTokenRefreshed,
+    RemoteProjectJoined,
+    RemoteProjectInvitationDiscarded,
+    PendingParticipantExpired,
+    LocalConnectionLost,
+    SessionSuperseded,
+    MuteRequested,
+    Error,
+    RoomLeft,
+    MediaUnavailable,
+}
+
+/// Cheap, cumulative counters surfacing server-compatibility problems that would otherwise be
+/// silently swallowed by [`Room::apply_room_update`]'s graceful per-participant error handling.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RoomMetrics {
+    pub participant_parse_errors: u64,
+}
+
+/// A snapshot of who's actually in the room versus still being invited, for a "3 in call, 1
+/// ringing" style summary. See [`Room::counts`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RoomCounts {
+    /// Remote participants who have joined the room, not counting the local user.
+    pub joined: usize,
+    /// Participants who've been invited but haven't joined or declined yet.
+    pub pending: usize,
+    /// The local user - always `1` while in a room. Broken out so callers don't have to add it
+    /// to `joined` themselves to get a total against [`Room::max_participants`].
+    pub local: usize,
+}
+
+/// A decision for a single pending (outstanding, not-yet-answered) call invite, as passed to
+/// [`Room::respond_to_calls`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallDecision {
+    /// Leave the invite outstanding.
+    Accept,
+    /// Cancel the invite.
+    Decline,
+}
+
+/// A summary of a batch [`Room::respond_to_calls`] request, for surfacing how many of a flood of
+/// pending invites actually got resolved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CallResponseSummary {
+    pub accepted: usize,
+    pub declined: usize,
+    pub failed: usize,
+}
+
+/// Context attached to [`Event::LocalConnectionLost`] so the reconnect UI (and support, via bug
+/// reports) can answer "why did I get dropped" without having to cross-reference logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisconnectDiagnostics {
+    /// A short description of the client's connection status at the moment the room noticed the
+    /// disconnect, e.g. `"disconnected"` or `"signed out"`.
+    pub last_client_status: String,
+    /// How long it had been since the last room update was successfully applied.
+    pub time_since_last_message: Duration,
+    /// How many reconnect attempts this room has already made this disconnection, before this
+    /// one. See [`Room::reconnect_attempts`].
+    pub reconnect_attempts: u32,
+}
+
+/// Counts of remote participants by [`ConnectionQuality`], for a session-health widget. See
+/// [`Room::connection_summary`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionSummary {
+    pub good: usize,
+    pub fair: usize,
+    pub poor: usize,
+    pub unknown: usize,
+}
+
+/// A recap of a finished room session, for telemetry. Returned by [`Room::leave`]/
+/// [`Room::leave_with_message`] once the room has actually gone offline.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SessionSummary {
+    pub duration: Duration,
+    /// The highest [`Room::participant_count`] (including ourselves) seen at any point in the
+    /// session, not just at the moment of leaving.
+    pub peak_participant_count: usize,
+    /// How many times the client successfully rejoined this room after losing its connection.
+    /// See [`Room::maintain_connection`].
+    pub reconnect_count: u32,
+}
+
+/// The outcome of asking to leave the room via [`Room::request_leave`].
+pub enum LeaveConfirmation {
+    /// No confirmation was needed - the leave is already underway.
+    Confirmed(Task<Result<SessionSummary>>),
+    /// The local user is the host of a room other participants are still in. Call
+    /// [`PendingLeave::confirm`] to leave anyway, or just drop this value to stay in the room.
+    NeedsConfirmation(PendingLeave),
+}
+
+/// A leave that's waiting on the UI to confirm it with the host. See [`Room::request_leave`].
+pub struct PendingLeave {
+    room: WeakModel<Room>,
+}
+
+impl PendingLeave {
+    pub fn confirm(self, cx: &mut AppContext) -> Task<Result<SessionSummary>> {
+        let Some(room) = self.room.upgrade() else {
+            return Task::ready(Err(anyhow!("room is already gone")));
+        };
+        room.update(cx, |room, cx| room.leave(cx))
+    }
+}
+
+/// See [`Room::persist_session`] and [`Room::restore_session`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomSessionState {
+    pub room_id: u64,
+    pub muted: bool,
+}
+
+/// A join or leave recorded in [`Room::audit_log`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEventKind {
+    Joined,
+    Left,
+}
+
+/// See [`Room::audit_log`].
+#[derive(Debug, Clone, Copy)]
+pub struct AuditEntry {
+    pub user_id: u64,
+    pub kind: AuditEventKind,
+    pub at: Instant,
+}
+
+/// How many of the most recent [`AuditEntry`] values [`Room::audit_log`] retains.
+const AUDIT_LOG_CAPACITY: usize = 100;
+
+/// Rooms whose initial roster is at least this large are applied in chunks by
+/// [`Room::apply_initial_roster`] instead of all at once, so the UI can start rendering
+/// participants before the whole roster has arrived.
+pub const INITIAL_ROSTER_CHUNK_THRESHOLD: usize = 50;
+
+/// How many participants [`Room::apply_initial_roster`] applies per chunk.
+pub const INITIAL_ROSTER_CHUNK_SIZE: usize = 25;
+
+#[cfg(any(test, feature = "test-support"))]
+static INITIAL_ROSTER_CHUNK_THRESHOLD_OVERRIDE: util::test::TestOverride =
+    util::test::TestOverride::new();
+
+#[cfg(any(test, feature = "test-support"))]
+static INITIAL_ROSTER_CHUNK_SIZE_OVERRIDE: util::test::TestOverride =
+    util::test::TestOverride::new();
+
+fn initial_roster_chunk_threshold() -> usize {
+    #[cfg(any(test, feature = "test-support"))]
+    if let Some(threshold) = INITIAL_ROSTER_CHUNK_THRESHOLD_OVERRIDE.get() {
+        return threshold as usize;
+    }
+    INITIAL_ROSTER_CHUNK_THRESHOLD
+}
+
+fn initial_roster_chunk_size() -> usize {
+    #[cfg(any(test, feature = "test-support"))]
+    if let Some(size) = INITIAL_ROSTER_CHUNK_SIZE_OVERRIDE.get() {
+        return size as usize;
+    }
+    INITIAL_ROSTER_CHUNK_SIZE
+}
+
+/// Overrides [`INITIAL_ROSTER_CHUNK_THRESHOLD`] for the current process, e.g. to exercise
+/// chunking in a test without having to join a room with that many real participants.
+#[cfg(any(test, feature = "test-support"))]
+pub fn set_initial_roster_chunk_threshold_for_test(threshold: usize) {
+    INITIAL_ROSTER_CHUNK_THRESHOLD_OVERRIDE.set(threshold as u64);
+}
+
+/// Overrides [`INITIAL_ROSTER_CHUNK_SIZE`] for the current process, e.g. to get multiple chunks
+/// out of a test room without having to join that many real participants.
+#[cfg(any(test, feature = "test-support"))]
+pub fn set_initial_roster_chunk_size_for_test(size: usize) {
+    INITIAL_ROSTER_CHUNK_SIZE_OVERRIDE.set(size as u64);
+}
+
+/// A handle to an in-progress [`Room::mic_test`] loopback. Dropping it without calling
+/// [`MicTestHandle::stop`] leaves the background sampling task running until it times out on its
+/// own after [`MIC_TEST_SAMPLE_COUNT`] samples.
+pub struct MicTestHandle {
+    stop_tx: watch::Sender<bool>,
+    levels_rx: watch::Receiver<f32>,
+}
+
+impl MicTestHandle {
+    /// A stream of sampled audio levels (0.0 silence - 1.0 peak), updated roughly every
+    /// [`MIC_TEST_SAMPLE_INTERVAL`] while the test is running.
+    pub fn levels(&self) -> watch::Receiver<f32> {
+        self.levels_rx.clone()
+    }
+
+    /// Ends the loopback immediately rather than waiting for it to time out.
+    pub fn stop(&mut self) {
+        self.stop_tx.try_send(true).ok();
+    }
 }
 
 pub struct Room {
@@ -62,25 +538,135 @@ pub struct Room {
     channel_id: Option<ChannelId>,
     live_kit: Option<LiveKitRoom>,
     status: RoomStatus,
+    reconnect_attempts: u32,
+    /// How many times the client has successfully rejoined this room after losing its
+    /// connection, across the room's whole lifetime. Unlike `reconnect_attempts`, this never
+    /// resets - see [`SessionSummary::reconnect_count`].
+    successful_reconnect_count: u32,
+    /// When this room was created, for [`SessionSummary::duration`].
+    created_at: Instant,
+    /// When a room update was last successfully applied, for
+    /// [`DisconnectDiagnostics::time_since_last_message`].
+    last_message_at: Instant,
+    /// The highest [`Room::participant_count`] observed so far, for
+    /// [`SessionSummary::peak_participant_count`]. Updated alongside
+    /// [`Room::check_capacity_crossing`], which already runs whenever the roster changes.
+    peak_participant_count: usize,
+    roster_before_disconnect: Option<HashSet<u64>>,
+    recently_departed: Vec<u64>,
     shared_projects: HashSet<WeakModel<Project>>,
+    shared_projects_order: Vec<WeakModel<Project>>,
     joined_projects: HashSet<WeakModel<Project>>,
     local_participant: LocalParticipant,
+    /// The buffer/file the local user currently has open, broadcast as part of `SharedProject`
+    /// locations. See [`Room::set_open_path`].
+    local_open_path: Option<ProjectPath>,
+    /// Where in `local_open_path` the local user's cursor/selection currently is, broadcast
+    /// alongside it. See [`Room::set_open_path`].
+    local_open_anchor: Option<ViewAnchor>,
+    /// The `(location, open_path, anchor)` triple from the most recent [`Room::set_location`]
+    /// call that actually went out over the wire. Lets a redundant `set_location` (e.g.
+    /// re-entering the project you're already broadcasting) skip the network round trip.
+    last_broadcast_location: Option<(ParticipantLocation, Option<ProjectPath>, Option<ViewAnchor>)>,
+    /// The in-flight `UpdateParticipantLocation` request kicked off by the most recent
+    /// [`Room::set_location`] call, if its response hasn't arrived yet. [`Room::leave_internal`]
+    /// waits for this to finish (or fail) before sending `LeaveRoom`, so a location update for
+    /// wherever the user was looking right before they left still reaches the server instead of
+    /// racing the leave. Shared so both the original caller and the leave flush can await the
+    /// same request rather than issuing it twice.
+    pending_location_broadcast: Option<Shared<Task<Result<ParticipantLocation, String>>>>,
     remote_participants: BTreeMap<u64, RemoteParticipant>,
+    /// Remote participant user ids in the order they most recently joined, for
+    /// [`Room::participants_by_recent_speech`]'s join-order fallback. `remote_participants` itself
+    /// is keyed (and iterates) by user id, which carries no join-order information.
+    remote_participant_order: Vec<u64>,
+    participant_handles: HashMap<u64, Model<RemoteParticipant>>,
+    /// Last known location of a participant who just left, kept for [`RECENT_LOCATION_TTL`] so a
+    /// quick rejoin (same user, new peer id) can restore it optimistically. See
+    /// [`Room::apply_room_update`].
+    recent_locations: HashMap<u64, (ParticipantLocation, Instant)>,
+    update_epoch: u64,
+    /// Bumped once per [`RoomUpdate::ActiveSpeakersChanged`] event and stamped onto every
+    /// participant reported as speaking, so [`Room::participants_by_recent_speech`] can sort by
+    /// recency without relying on wall-clock time.
+    speech_sequence: u64,
     pending_participants: Vec<Arc<User>>,
+    pending_participant_expirations: HashMap<u64, Task<()>>,
     participant_user_ids: HashSet<u64>,
     pending_call_count: usize,
+    outgoing_calls: Vec<u64>,
+    in_flight_calls: HashMap<u64, Shared<Task<Result<(), String>>>>,
+    max_participants: Option<usize>,
+    at_capacity: bool,
+    /// Invites queued via [`Room::queue_call`] because the room was full when they were placed,
+    /// in the order they should be dispatched once a slot opens up.
+    queued_calls: Vec<u64>,
+    /// The fraction of the roster that [`Room::apply_room_update`] will tolerate losing in a
+    /// single diff before treating it as a suspicious, possibly-corrupted delta and requesting a
+    /// full [`Room::resync`] instead of applying the removals. See
+    /// [`Room::set_mass_removal_resync_threshold`].
+    mass_removal_resync_threshold: f64,
+    metrics: RoomMetrics,
+    /// How many remote participants are currently in each shared project, for a "3 people
+    /// editing" badge. Recomputed from participant locations at the end of every
+    /// [`Room::apply_room_update`]; projects with a count of `0` are pruned rather than kept
+    /// around at zero. See [`Event::ProjectOccupancyChanged`].
+    project_occupancy: HashMap<u64, usize>,
+    /// The resolution last requested, via [`Room::request_video_quality`], for each remote
+    /// peer's video stream.
+    requested_video_qualities: HashMap<PeerId, VideoQuality>,
+    last_known_participants: Vec<RemoteParticipant>,
+    media_token: Option<String>,
     leave_when_empty: bool,
     client: Arc<Client>,
     user_store: Model<UserStore>,
     follows_by_leader_id_project_id: HashMap<(PeerId, u64), Vec<PeerId>>,
+    /// Who the local user is currently following, by user id. Deliberately left untouched by
+    /// [`Room::clear_state`] so a disconnect doesn't cancel it - [`Room::apply_room_update`]
+    /// resumes following as soon as this user reappears in the roster. See [`Room::follow`].
+    local_follow_target: Option<u64>,
+    /// Cancels itself (dropping a [`Task`] cancels it) once the followed leader reappears or the
+    /// user calls [`Room::unfollow`]. See [`Room::expire_follow_target`].
+    follow_target_timeout: Option<Task<()>>,
     client_subscriptions: Vec<client::Subscription>,
     _subscriptions: Vec<gpui::Subscription>,
     room_update_completed_tx: watch::Sender<Option<()>>,
     room_update_completed_rx: watch::Receiver<Option<()>>,
     pending_room_update: Option<Task<()>>,
     maintain_connection: Option<Task<Option<()>>>,
+    /// The in-flight LiveKit mute-publish request, if any. Replacing this (rather than letting
+    /// `toggle_mute`/`toggle_deafen` fire-and-forget) cancels a superseded request before it can
+    /// land, so a stale ack can never overwrite the effect of whatever the user's most recent
+    /// toggle intended - local intent (`LiveKitRoom::muted_by_user`) always wins.
+    pending_mute_update: Option<Task<()>>,
+    /// Whether the host (or a co-host) has forced this participant's microphone off via
+    /// [`Room::mute_participant_remotely`]. See [`Room::is_force_muted`] and [`Room::unmute`].
+    force_muted: bool,
+    /// Whether push-to-talk mode is enabled - the mic is muted by default and only unmuted while
+    /// [`Room::push_to_talk_begin`]/[`Room::push_to_talk_end`] bracket a held key. See
+    /// [`Room::set_push_to_talk`].
+    push_to_talk: bool,
+    /// Whether the app is currently in the foreground. See [`Room::set_foreground`].
+    foreground: bool,
+    /// The project we were broadcasting as our location when we were last backgrounded, to
+    /// restore once foregrounded again. See [`Room::set_foreground`].
+    backgrounded_active_project: Option<WeakModel<Project>>,
+    /// Callbacks registered via [`Room::on_offline`], run whenever the room transitions to
+    /// [`RoomStatus::Offline`].
+    offline_callbacks: Vec<Box<dyn Fn(&mut AppContext)>>,
+    recent_events: VecDeque<Event>,
+    /// Bounded join/leave history. See [`Room::audit_log`].
+    audit_log: Vec<AuditEntry>,
+    /// How many `UpdateParticipantLocation` requests [`Room::set_location`] has actually sent,
+    /// for asserting that coalescing a redundant call skips the round trip.
+    #[cfg(any(test, feature = "test-support"))]
+    location_broadcasts_sent: usize,
 }
 
+/// How many of the most recent [replayable](Room::is_replayable) events [`Room::subscribe_with_replay`]
+/// delivers to a new subscriber before live events.
+const REPLAY_BUFFER_CAPACITY: usize = 4;
+
 impl EventEmitter<Event> for Room {}
 
 impl Room {
@@ -92,6 +678,65 @@ impl Room {
         !self.shared_projects.is_empty()
     }
 
+    /// Returns the local participant's shared projects in publish order, which is stable for
+    /// the UI even after an earlier-published project is unshared.
+    pub fn shared_projects_in_order(&self) -> impl Iterator<Item = Model<Project>> + '_ {
+        self.shared_projects_order
+            .iter()
+            .filter_map(|project| project.upgrade())
+    }
+
+    /// Guard shared by the methods that need an actual connection to do their work. Treats
+    /// `Rejoining` the same as `Offline`, since issuing a fresh request mid-rejoin would race
+    /// the rejoin's own request rather than queue behind it.
+    fn ensure_connected(&self) -> Result<()> {
+        match self.status {
+            RoomStatus::Online => Ok(()),
+            RoomStatus::Rejoining => Err(anyhow!("room is reconnecting")),
+            RoomStatus::Offline => Err(anyhow!("room is offline")),
+        }
+    }
+
+    /// Sends a lightweight request to the server and returns how long it took to round-trip,
+    /// useful for a "test connection" affordance.
+    pub fn ping(&self, cx: &ModelContext<Self>) -> Task<Result<Duration>> {
+        if let Err(error) = self.ensure_connected() {
+            return Task::ready(Err(error));
+        }
+
+        let client = self.client.clone();
+        cx.background_executor().spawn(async move {
+            let started_at = Instant::now();
+            client.request(proto::Ping {}).await?;
+            Ok(started_at.elapsed())
+        })
+    }
+
+    /// Samples local audio levels for a "test your mic" affordance, without publishing anything
+    /// to the room - a no-op with respect to whatever [`Room::share_microphone`] is already
+    /// broadcasting, and usable regardless of [`Room::status`]. There's no real audio capture or
+    /// level-metering primitive in this codebase yet (`audio` only plays canned sound effects;
+    /// `live_kit_client` doesn't expose input levels), so this samples a fixed placeholder level
+    /// rather than anything actually derived from the microphone, giving callers a stable
+    /// [`MicTestHandle`] to build a "test your mic" UI against once real metering lands.
+    pub fn mic_test(&self, cx: &ModelContext<Self>) -> Task<Result<MicTestHandle>> {
+        let (stop_tx, mut stop_rx) = watch::channel_with(false);
+        let (mut levels_tx, levels_rx) = watch::channel_with(0.0);
+        let background_executor = cx.background_executor().clone();
+        cx.background_executor()
+            .spawn(async move {
+                for _ in 0..MIC_TEST_SAMPLE_COUNT {
+                    if *stop_rx.borrow() {
+                        break;
+                    }
+                    levels_tx.try_send(MIC_TEST_PLACEHOLDER_LEVEL).ok();
+                    background_executor.timer(MIC_TEST_SAMPLE_INTERVAL).await;
+                }
+            })
+            .detach();
+        Task::ready(Ok(MicTestHandle { stop_tx, levels_rx }))
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn is_connected(&self) -> bool {
         if let Some(live_kit) = self.live_kit.as_ref() {
@@ -112,6 +757,9 @@ impl Room {
         user_store: Model<UserStore>,
         cx: &mut ModelContext<Self>,
     ) -> Self {
+        let media_token = live_kit_connection_info
+            .as_ref()
+            .map(|info| info.token.clone());
         let live_kit_room = if let Some(connection_info) = live_kit_connection_info {
             let room = live_kit_client::Room::new();
             let mut status = room.status();
@@ -154,7 +802,21 @@ impl Room {
 
             let connect = room.connect(&connection_info.server_url, &connection_info.token);
             cx.spawn(|this, mut cx| async move {
-                connect.await?;
+                // If the backend itself never comes up (e.g. LiveKit is unreachable), fall back
+                // to text-only collaboration rather than leaving the room in a half-connected
+                // state where `share_microphone`/`share_screen` would eventually fail on their
+                // own, more confusingly, once they got around to touching `live_kit`.
+                if let Err(error) = connect.await {
+                    return this.update(&mut cx, |this, cx| {
+                        this.live_kit.take();
+                        this.emit_event(
+                            Event::MediaUnavailable {
+                                reason: error.to_string(),
+                            },
+                            cx,
+                        );
+                    });
+                }
                 this.update(&mut cx, |this, cx| {
                     if this.can_use_microphone() {
                         if let Some(live_kit) = &this.live_kit {
@@ -198,15 +860,48 @@ impl Room {
             channel_id,
             live_kit: live_kit_room,
             status: RoomStatus::Online,
+            reconnect_attempts: 0,
+            successful_reconnect_count: 0,
+            created_at: Instant::now(),
+            last_message_at: Instant::now(),
+            peak_participant_count: 1,
+            roster_before_disconnect: None,
+            recently_departed: Default::default(),
             shared_projects: Default::default(),
+            shared_projects_order: Default::default(),
             joined_projects: Default::default(),
             participant_user_ids: Default::default(),
             local_participant: Default::default(),
+            local_open_path: None,
+            local_open_anchor: None,
+            last_broadcast_location: None,
+            pending_location_broadcast: None,
             remote_participants: Default::default(),
+            remote_participant_order: Default::default(),
+            participant_handles: Default::default(),
+            recent_locations: Default::default(),
+            update_epoch: 0,
+            speech_sequence: 0,
             pending_participants: Default::default(),
+            pending_participant_expirations: Default::default(),
             pending_call_count: 0,
+            outgoing_calls: Default::default(),
+            in_flight_calls: Default::default(),
+            max_participants: None,
+            at_capacity: false,
+            queued_calls: Default::default(),
+            mass_removal_resync_threshold: DEFAULT_MASS_REMOVAL_RESYNC_THRESHOLD,
+            metrics: RoomMetrics::default(),
+            project_occupancy: Default::default(),
+            requested_video_qualities: Default::default(),
+            last_known_participants: Default::default(),
+            media_token,
             client_subscriptions: vec![
-                client.add_message_handler(cx.weak_model(), Self::handle_room_updated)
+                client.add_message_handler(cx.weak_model(), Self::handle_room_updated),
+                client.add_message_handler(cx.weak_model(), Self::handle_participant_left),
+                client.add_message_handler(cx.weak_model(), Self::handle_force_mute),
+                client.add_message_handler(cx.weak_model(), Self::handle_request_mute),
+                client.add_message_handler(cx.weak_model(), Self::handle_participant_activity),
             ],
             _subscriptions: vec![
                 cx.on_release(Self::released),
@@ -217,15 +912,28 @@ impl Room {
             client,
             user_store,
             follows_by_leader_id_project_id: Default::default(),
+            local_follow_target: None,
+            follow_target_timeout: None,
             maintain_connection: Some(maintain_connection),
+            pending_mute_update: None,
+            force_muted: false,
+            push_to_talk: false,
+            foreground: true,
+            backgrounded_active_project: None,
+            offline_callbacks: Vec::new(),
             room_update_completed_tx,
             room_update_completed_rx,
+            recent_events: Default::default(),
+            audit_log: Default::default(),
+            #[cfg(any(test, feature = "test-support"))]
+            location_broadcasts_sent: 0,
         }
     }
 
     pub(crate) fn create(
         called_user_id: u64,
         initial_project: Option<Model<Project>>,
+        context: Option<String>,
         client: Arc<Client>,
         user_store: Model<UserStore>,
         cx: &mut AppContext,
@@ -262,7 +970,7 @@ impl Room {
             let did_join = room
                 .update(&mut cx, |room, cx| {
                     room.leave_when_empty = true;
-                    room.call(called_user_id, initial_project_id, cx)
+                    room.call_with_context(called_user_id, initial_project_id, context, cx)
                 })?
                 .await;
             match did_join {
@@ -278,16 +986,11 @@ impl Room {
         user_store: Model<UserStore>,
         cx: AsyncAppContext,
     ) -> Result<Model<Self>> {
-        Self::from_join_response(
-            client
-                .request(proto::JoinChannel {
-                    channel_id: channel_id.0,
-                })
-                .await?,
-            client,
-            user_store,
-            cx,
-        )
+        let request = client.request(proto::JoinChannel {
+            channel_id: channel_id.0,
+        });
+        let response = Self::request_with_join_timeout(request, &cx).await?;
+        Self::from_join_response(response, client, user_store, cx)
     }
 
     pub(crate) async fn join(
@@ -296,23 +999,100 @@ impl Room {
         user_store: Model<UserStore>,
         cx: AsyncAppContext,
     ) -> Result<Model<Self>> {
-        Self::from_join_response(
-            client.request(proto::JoinRoom { id: room_id }).await?,
-            client,
-            user_store,
-            cx,
-        )
+        let request = client.request(proto::JoinRoom { id: room_id });
+        let response = Self::request_with_join_timeout(request, &cx).await?;
+        Self::from_join_response(response, client, user_store, cx)
+    }
+
+    /// Like [`Self::join`], but if the caller already has a `proto::Room` on hand - e.g. from a
+    /// list-rooms response - renders that roster immediately instead of waiting on the
+    /// authoritative `JoinRoom` round-trip, then reconciles once the real response arrives. Falls
+    /// back to [`Self::join`] when `prefetched_room` is `None`.
+    ///
+    /// Realtime audio/video can only be established from the initial `JoinRoom` response, so if
+    /// the authoritative response carries `live_kit_connection_info`, it's logged and otherwise
+    /// ignored here; a caller that needs realtime media immediately should use `join` instead.
+    pub(crate) async fn join_with_prefetched_room(
+        room_id: u64,
+        prefetched_room: Option<proto::Room>,
+        client: Arc<Client>,
+        user_store: Model<UserStore>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Model<Self>> {
+        let Some(prefetched_room) = prefetched_room else {
+            return Self::join(room_id, client, user_store, cx).await;
+        };
+
+        let room = cx.new_model(|cx| {
+            Self::new(
+                prefetched_room.id,
+                None,
+                None,
+                client.clone(),
+                user_store.clone(),
+                cx,
+            )
+        })?;
+        room.update(&mut cx, |room, cx| {
+            room.leave_when_empty = room.channel_id.is_none();
+            room.apply_room_update(prefetched_room, cx)
+        })??;
+
+        let request = client.request(proto::JoinRoom { id: room_id });
+        cx.spawn({
+            let room = room.clone();
+            |cx| Self::reconcile_prefetched_room(room, request, cx)
+        })
+        .detach();
+
+        Ok(room)
+    }
+
+    async fn reconcile_prefetched_room(
+        room: Model<Self>,
+        request: impl Future<Output = Result<proto::JoinRoomResponse>>,
+        mut cx: AsyncAppContext,
+    ) {
+        let result = async {
+            let response = Self::request_with_join_timeout(request, &cx).await?;
+            let room_proto = response.room.ok_or_else(|| anyhow!("invalid room"))?;
+            if response.live_kit_connection_info.is_some() {
+                log::warn!("ignoring live kit connection info for a room joined via prefetch");
+            }
+            room.update(&mut cx, |room, cx| {
+                room.channel_id = response.channel_id.map(ChannelId);
+                room.apply_room_update(room_proto, cx)
+            })??;
+            anyhow::Ok(())
+        }
+        .await;
+        result.log_err();
+    }
+
+    /// Races `request` against [`JOIN_TIMEOUT`], discarding the request on expiry so no partial
+    /// room state gets created for a server that never responds to a join.
+    async fn request_with_join_timeout<T>(
+        request: impl Future<Output = Result<T>>,
+        cx: &AsyncAppContext,
+    ) -> Result<T> {
+        let timeout = cx.background_executor().timer(join_timeout()).fuse();
+        let request = request.fuse();
+        futures::pin_mut!(request, timeout);
+        futures::select_biased! {
+            response = request => response,
+            _ = timeout => Err(anyhow!("joining room timed out")),
+        }
     }
 
     fn released(&mut self, cx: &mut AppContext) {
         if self.status.is_online() {
-            self.leave_internal(cx).detach_and_log_err(cx);
+            self.leave_internal(None, true, cx).detach_and_log_err(cx);
         }
     }
 
     fn app_will_quit(&mut self, cx: &mut ModelContext<Self>) -> impl Future<Output = ()> {
         let task = if self.status.is_online() {
-            let leave = self.leave_internal(cx);
+            let leave = self.leave_internal(None, true, cx);
             Some(cx.background_executor().spawn(async move {
                 leave.await.log_err();
             }))
@@ -337,6 +1117,20 @@ impl Room {
         user_store: Model<UserStore>,
         mut cx: AsyncAppContext,
     ) -> Result<Model<Self>> {
+        // The connection handshake already rejects a server speaking a different RPC protocol
+        // version before any request can be made, so this should be unreachable in practice -
+        // but check again here in case something upstream (a proxy, a stale pooled connection)
+        // let a mismatched handshake through, since misinterpreting `apply_room_update` silently
+        // is worse than failing the join loudly.
+        if let Some(server_protocol_version) = response.protocol_version {
+            if server_protocol_version != client::PROTOCOL_VERSION {
+                return Err(anyhow!(
+                    "cannot join room: server speaks protocol version {server_protocol_version}, this client speaks {}",
+                    client::PROTOCOL_VERSION
+                ));
+            }
+        }
+
         let room_proto = response.room.ok_or_else(|| anyhow!("invalid room"))?;
         let room = cx.new_model(|cx| {
             Self::new(
@@ -348,14 +1142,61 @@ impl Room {
                 cx,
             )
         })?;
-        room.update(&mut cx, |room, cx| {
+        room.update(&mut cx, |room, _| {
             room.leave_when_empty = room.channel_id.is_none();
-            room.apply_room_update(room_proto, cx)?;
-            anyhow::Ok(())
-        })??;
+        })?;
+        cx.spawn({
+            let room = room.clone();
+            |cx| Self::apply_initial_roster(room, room_proto, cx)
+        })
+        .detach();
         Ok(room)
     }
 
+    /// Applies the `proto::Room` received from joining, in the background so [`Self::join`]
+    /// returns as soon as the (still-empty) room exists rather than once the whole roster has
+    /// landed. Rooms with at least [`INITIAL_ROSTER_CHUNK_THRESHOLD`] participants are applied
+    /// [`INITIAL_ROSTER_CHUNK_SIZE`] at a time instead of all at once, so the UI can start
+    /// rendering participants as they arrive rather than waiting on one giant update; each
+    /// newly-applied participant fires [`Event::ParticipantJoined`] as its chunk lands. Either
+    /// way, [`Event::RosterComplete`] fires exactly once, after the whole roster - including
+    /// pending invitees and our own participant record - has been applied.
+    async fn apply_initial_roster(
+        room: Model<Self>,
+        room_proto: proto::Room,
+        mut cx: AsyncAppContext,
+    ) {
+        async fn apply_and_wait(
+            room: &Model<Room>,
+            room_proto: proto::Room,
+            cx: &mut AsyncAppContext,
+        ) -> Result<()> {
+            room.update(cx, |room, cx| room.apply_room_update(room_proto, cx))??;
+            let pending_update = room.update(cx, |room, _| room.pending_room_update.take())?;
+            if let Some(pending_update) = pending_update {
+                pending_update.await;
+            }
+            Ok(())
+        }
+
+        let result = async {
+            if room_proto.participants.len() < initial_roster_chunk_threshold() {
+                apply_and_wait(&room, room_proto, &mut cx).await?;
+            } else {
+                let mut chunk = room_proto.clone();
+                chunk.participants.clear();
+                for participants in room_proto.participants.chunks(initial_roster_chunk_size()) {
+                    chunk.participants.extend_from_slice(participants);
+                    apply_and_wait(&room, chunk.clone(), &mut cx).await?;
+                }
+            }
+            room.update(&mut cx, |room, cx| room.emit_event(Event::RosterComplete, cx))?;
+            anyhow::Ok(())
+        }
+        .await;
+        result.log_err();
+    }
+
     fn should_leave(&self) -> bool {
         self.leave_when_empty
             && self.pending_room_update.is_none()
@@ -364,12 +1205,54 @@ impl Room {
             && self.pending_call_count == 0
     }
 
-    pub(crate) fn leave(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+    pub(crate) fn leave(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<SessionSummary>> {
+        self.leave_with_message(None, cx)
+    }
+
+    /// Like [`Room::leave`], but gives the host of a room that still has other people in it a
+    /// chance to back out before actually disconnecting - leaving as the host ends the call for
+    /// everyone, so it's easy to do by accident. Non-hosts, and hosts of an otherwise-empty
+    /// room, leave immediately with no confirmation.
+    pub fn request_leave(&mut self, cx: &mut ModelContext<Self>) -> LeaveConfirmation {
+        if self.local_participant_is_admin() && !self.remote_participants.is_empty() {
+            LeaveConfirmation::NeedsConfirmation(PendingLeave {
+                room: cx.weak_model(),
+            })
+        } else {
+            LeaveConfirmation::Confirmed(self.leave(cx))
+        }
+    }
+
+    /// Like `leave`, but lets the caller attach a short farewell that's broadcast to the other
+    /// participants as a `ParticipantFarewell` event, since by the time the next room snapshot
+    /// arrives the departing participant is simply gone from it.
+    pub(crate) fn leave_with_message(
+        &mut self,
+        message: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<SessionSummary>> {
+        cx.notify();
+        self.leave_internal(message, true, cx)
+    }
+
+    /// Leaves without discarding the last-known roster (see [`Room::last_known_participants`]),
+    /// so the reconnect UI can keep showing who was in the call, grayed out, instead of an empty
+    /// room. Used when the client itself lost its connection, as opposed to the user explicitly
+    /// choosing to leave.
+    fn leave_due_to_disconnect(
+        &mut self,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<SessionSummary>> {
         cx.notify();
-        self.leave_internal(cx)
+        self.leave_internal(None, false, cx)
     }
 
-    fn leave_internal(&mut self, cx: &mut AppContext) -> Task<Result<()>> {
+    fn leave_internal(
+        &mut self,
+        message: Option<String>,
+        user_initiated: bool,
+        cx: &mut AppContext,
+    ) -> Task<Result<SessionSummary>> {
         if self.status.is_offline() {
             return Task::ready(Err(anyhow!("room is offline")));
         }
@@ -377,15 +1260,59 @@ impl Room {
         log::info!("leaving room");
         Audio::play_sound(Sound::Leave, cx);
 
+        let summary = SessionSummary {
+            duration: self.created_at.elapsed(),
+            peak_participant_count: self.peak_participant_count,
+            reconnect_count: self.successful_reconnect_count,
+        };
+
+        let pending_location_broadcast = self.pending_location_broadcast.take();
         self.clear_state(cx);
+        if user_initiated {
+            self.last_known_participants.clear();
+        }
 
-        let leave_room = self.client.request(proto::LeaveRoom {});
+        let leave_room = self.client.request(proto::LeaveRoom {
+            farewell_message: message,
+        });
         cx.background_executor().spawn(async move {
+            // Give a location broadcast that was already in flight a chance to land before
+            // telling the server we're gone, so wherever the user was looking right before they
+            // left isn't lost to a race with the leave itself. Its failure isn't fatal - leaving
+            // the room matters more than one last location update.
+            if let Some(pending_location_broadcast) = pending_location_broadcast {
+                pending_location_broadcast.await.ok();
+            }
             leave_room.await?;
-            anyhow::Ok(())
+            anyhow::Ok(summary)
         })
     }
 
+    /// Registers `callback` to run whenever this room transitions to [`RoomStatus::Offline`] -
+    /// an explicit leave, or losing the connection for good - for releasing resources tied to
+    /// the call. A lighter-weight alternative to subscribing to this room's events and matching
+    /// on a status change yourself. Multiple callbacks can be registered; all of them run, in
+    /// registration order, each time the room goes offline.
+    pub fn on_offline(&mut self, callback: impl Fn(&mut AppContext) + 'static) {
+        self.offline_callbacks.push(Box::new(callback));
+    }
+
+    /// Called when the server reports, via [`proto::SessionSuperseded`], that this connection's
+    /// membership in the room was taken over by another session of the same user. Transitions
+    /// straight to [`RoomStatus::Offline`] without sending `LeaveRoom`, since the server has
+    /// already dropped this connection from the room, and emits [`Event::SessionSuperseded`] so
+    /// the UI can explain why.
+    pub(crate) fn handle_session_superseded(&mut self, reason: String, cx: &mut ModelContext<Self>) {
+        if self.status.is_offline() {
+            return;
+        }
+
+        log::info!("session superseded: {reason}");
+        self.clear_state(cx);
+        self.emit_event(Event::SessionSuperseded { reason }, cx);
+        cx.notify();
+    }
+
     pub(crate) fn clear_state(&mut self, cx: &mut AppContext) {
         for project in self.shared_projects.drain() {
             if let Some(project) = project.upgrade() {
@@ -394,6 +1321,7 @@ impl Room {
                 });
             }
         }
+        self.shared_projects_order.clear();
         for project in self.joined_projects.drain() {
             if let Some(project) = project.upgrade() {
                 project.update(cx, |project, cx| {
@@ -404,13 +1332,27 @@ impl Room {
         }
 
         self.status = RoomStatus::Offline;
+        for callback in &self.offline_callbacks {
+            callback(cx);
+        }
+        self.last_known_participants = self.remote_participants.values().cloned().collect();
         self.remote_participants.clear();
+        self.remote_participant_order.clear();
+        self.participant_handles.clear();
         self.pending_participants.clear();
+        self.pending_participant_expirations.clear();
         self.participant_user_ids.clear();
         self.client_subscriptions.clear();
         self.live_kit.take();
         self.pending_room_update.take();
         self.maintain_connection.take();
+        self.pending_mute_update.take();
+        // The leader we were following is never coming back once we're actually leaving, so
+        // don't let this timer keep running just to fire `expire_follow_target` into the void.
+        self.follow_target_timeout.take();
+        self.force_muted = false;
+        self.last_broadcast_location = None;
+        self.pending_location_broadcast = None;
     }
 
     async fn maintain_connection(
@@ -430,6 +1372,22 @@ impl Room {
                     .ok_or_else(|| anyhow!("room was dropped"))?
                     .update(&mut cx, |this, cx| {
                         this.status = RoomStatus::Rejoining;
+                        this.roster_before_disconnect = Some(this.participant_user_ids.clone());
+                        if let Some(leader_id) = this.local_follow_target {
+                            this.follow_target_timeout = Some(cx.spawn(move |this, mut cx| async move {
+                                cx.background_executor().timer(follow_target_timeout()).await;
+                                this.update(&mut cx, |this, cx| {
+                                    this.expire_follow_target(leader_id, cx)
+                                })
+                                .ok();
+                            }));
+                        }
+                        let diagnostics = DisconnectDiagnostics {
+                            last_client_status: format!("{:?}", *client_status.borrow()),
+                            time_since_last_message: this.last_message_at.elapsed(),
+                            reconnect_attempts: this.reconnect_attempts,
+                        };
+                        this.emit_event(Event::LocalConnectionLost { diagnostics }, cx);
                         cx.notify();
                     })?;
 
@@ -447,9 +1405,18 @@ impl Room {
                                 match this.update(&mut cx, |this, cx| this.rejoin(cx)) {
                                     Ok(task) => {
                                         if task.await.log_err().is_some() {
+                                            this.update(&mut cx, |this, _| {
+                                                this.reconnect_attempts = 0;
+                                                this.successful_reconnect_count += 1;
+                                            })
+                                            .ok();
                                             return true;
                                         } else {
                                             remaining_attempts -= 1;
+                                            this.update(&mut cx, |this, _| {
+                                                this.reconnect_attempts += 1
+                                            })
+                                            .ok();
                                         }
                                     }
                                     Err(_app_dropped) => return false,
@@ -493,7 +1460,8 @@ impl Room {
         // we leave the room and return an error.
         if let Some(this) = this.upgrade() {
             log::info!("reconnection failed, leaving room");
-            this.update(&mut cx, |this, cx| this.leave(cx))?.await?;
+            this.update(&mut cx, |this, cx| this.leave_due_to_disconnect(cx))?
+                .await?;
         }
         Err(anyhow!(
             "can't reconnect to room: client failed to re-establish connection"
@@ -578,36 +1546,721 @@ impl Room {
         })
     }
 
-    pub fn id(&self) -> u64 {
-        self.id
+    /// Requests a fresh snapshot of the room from the server and reapplies it from scratch.
+    /// Used as a recovery mechanism when local room state may have drifted from the server's,
+    /// e.g. after `apply_room_update` fails to apply an update.
+    pub fn resync(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        self.rejoin(cx)
     }
 
-    pub fn status(&self) -> RoomStatus {
-        self.status
+    /// Whether `event` is safe to replay to a subscriber well after it originally fired.
+    /// Replayable events are coarse, idempotent snapshots of room-wide state (e.g. "the room
+    /// is full") rather than events tied to a specific in-flight participant or project that
+    /// may have since left or been unshared, which would be misleading to replay stale.
+    /// [`Event::RoomJoined`] is deliberately excluded: it's fired by [`crate::ActiveCall`] when
+    /// it adopts a room, not by the room itself, so it's out of scope for this buffer.
+    fn is_replayable(event: &Event) -> bool {
+        matches!(event, Event::RoomFull | Event::RoomHasCapacity)
     }
 
-    pub fn local_participant(&self) -> &LocalParticipant {
-        &self.local_participant
+    /// Emits `event`, first recording it in the replay buffer if [`Room::is_replayable`]. All
+    /// emissions of [`Event`] should go through here rather than `cx.emit` directly, so the
+    /// buffer stays in sync with what subscribers actually saw.
+    fn emit_event(&mut self, event: Event, cx: &mut ModelContext<Self>) {
+        if Self::is_replayable(&event) {
+            self.recent_events.push_back(event.clone());
+            while self.recent_events.len() > REPLAY_BUFFER_CAPACITY {
+                self.recent_events.pop_front();
+            }
+        }
+        cx.emit(event);
     }
 
-    pub fn remote_participants(&self) -> &BTreeMap<u64, RemoteParticipant> {
-        &self.remote_participants
+    /// Like [`gpui::ModelContext::subscribe`], but first delivers the buffered
+    /// [replayable](Room::is_replayable) events that already fired, so a subscriber created
+    /// slightly after the room won't miss something like [`Event::RoomFull`]. Takes `this`
+    /// explicitly (rather than going through `cx.update`) so the replay can run immediately,
+    /// before the live subscription is even registered.
+    pub fn subscribe_with_replay<T: 'static>(
+        room: &Model<Self>,
+        this: &mut T,
+        cx: &mut ModelContext<T>,
+        mut on_event: impl FnMut(&mut T, Model<Self>, &Event, &mut ModelContext<T>) + 'static,
+    ) -> gpui::Subscription {
+        for event in room.read(cx).recent_events.clone() {
+            on_event(this, room.clone(), &event, cx);
+        }
+        cx.subscribe(room, on_event)
     }
 
-    pub fn remote_participant_for_peer_id(&self, peer_id: PeerId) -> Option<&RemoteParticipant> {
-        self.remote_participants
-            .values()
-            .find(|p| p.peer_id == peer_id)
+    /// Like [`gpui::AppContext::subscribe`], but only invokes `on_event` for events whose
+    /// [`Event::kind`] matches `filter`, sparing callers that only care about one variant from
+    /// matching out everything else.
+    pub fn on_event(
+        room: &Model<Self>,
+        filter: EventKind,
+        cx: &mut AppContext,
+        mut on_event: impl FnMut(Model<Self>, &Event, &mut AppContext) + 'static,
+    ) -> gpui::Subscription {
+        cx.subscribe(room, move |room, event, cx| {
+            if event.kind() == filter {
+                on_event(room, event, cx);
+            }
+        })
     }
 
-    pub fn role_for_user(&self, user_id: u64) -> Option<proto::ChannelRole> {
-        self.remote_participants
-            .get(&user_id)
-            .map(|participant| participant.role)
+    pub fn id(&self) -> u64 {
+        self.id
     }
 
-    pub fn contains_guests(&self) -> bool {
-        self.local_participant.role == proto::ChannelRole::Guest
+    pub fn status(&self) -> RoomStatus {
+        self.status
+    }
+
+    /// Captures just enough client-side state to rejoin this room and look the same afterwards,
+    /// for callers that want to survive e.g. an app restart. Deliberately thin: project
+    /// membership isn't captured here, since re-sharing a project needs the `Model<Project>`
+    /// handle itself, which the caller already has and can pass back into `share_project` after
+    /// [`Room::restore_session`] returns.
+    pub fn persist_session(&self) -> RoomSessionState {
+        RoomSessionState {
+            room_id: self.id,
+            muted: self.is_muted(),
+        }
+    }
+
+    /// Rejoins the room described by `state` and reapplies the local mute state it captured.
+    /// Goes through [`proto::RejoinRoom`] rather than [`proto::JoinRoom`], since the latter
+    /// requires a fresh invitation - this is meant for resuming a call the local user was
+    /// already in (e.g. across an app restart) without anyone needing to call them again.
+    /// See [`Room::persist_session`].
+    pub async fn restore_session(
+        state: RoomSessionState,
+        client: Arc<Client>,
+        user_store: Model<UserStore>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Model<Self>> {
+        let response = client
+            .request(proto::RejoinRoom {
+                id: state.room_id,
+                reshared_projects: Vec::new(),
+                rejoined_projects: Vec::new(),
+            })
+            .await?;
+        let room_proto = response.room.ok_or_else(|| anyhow!("invalid room"))?;
+        let room = cx.new_model(|cx| Self::new(room_proto.id, None, None, client, user_store, cx))?;
+        room.update(&mut cx, |room, cx| -> Result<()> {
+            room.leave_when_empty = true;
+            room.apply_room_update(room_proto, cx)?;
+            if state.muted && !room.is_muted() {
+                room.toggle_mute(cx);
+            }
+            Ok(())
+        })??;
+        Ok(room)
+    }
+
+    /// Resolves once the room reaches `target`, immediately if it's already there. Useful for
+    /// tests and orchestration code that wants to wait for e.g. `RoomStatus::Online`.
+    pub fn await_status(&self, target: RoomStatus, cx: &mut ModelContext<Self>) -> Task<()> {
+        if self.status == target {
+            return Task::ready(());
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let mut tx = Some(tx);
+        let subscription = cx.observe(&cx.handle(), move |this, _, _| {
+            if this.status == target {
+                if let Some(tx) = tx.take() {
+                    tx.send(()).ok();
+                }
+            }
+        });
+
+        cx.spawn(|_, _| async move {
+            rx.await.ok();
+            drop(subscription);
+        })
+    }
+
+    /// Returns the ids of participants who left while this client was disconnected, so the UI
+    /// can show "Alice and Bob left while you were away." Clears the list once read.
+    pub fn recently_departed(&mut self) -> Vec<u64> {
+        mem::take(&mut self.recently_departed)
+    }
+
+    pub fn is_reconnecting(&self) -> bool {
+        self.status.is_rejoining()
+    }
+
+    /// Number of consecutive rejoin attempts that have failed since the last successful
+    /// reconnect, so the UI can show "reconnecting (attempt 3)".
+    pub fn reconnect_attempts(&self) -> u32 {
+        self.reconnect_attempts
+    }
+
+    /// Returns the underlying client for advanced integrations (e.g. fetching user profiles
+    /// for participants). Sending raw room messages through it directly is unsupported.
+    pub fn client(&self) -> &Arc<Client> {
+        &self.client
+    }
+
+    pub fn local_participant(&self) -> &LocalParticipant {
+        &self.local_participant
+    }
+
+    pub fn remote_participants(&self) -> &BTreeMap<u64, RemoteParticipant> {
+        &self.remote_participants
+    }
+
+    /// Remote participants who can't currently speak - either they have no microphone at all, or
+    /// they've denied the OS/browser permission to use one - as opposed to merely being muted.
+    /// See [`proto::MicState`].
+    pub fn participants_without_mic(&self) -> Vec<PeerId> {
+        self.remote_participants
+            .values()
+            .filter(|participant| {
+                matches!(
+                    participant.mic_state,
+                    proto::MicState::NoDevice | proto::MicState::Denied
+                )
+            })
+            .map(|participant| participant.peer_id)
+            .collect()
+    }
+
+    /// Remote participants currently showing as actively editing - see
+    /// [`RemoteParticipant::is_active`] - for a lightweight "Bob is typing" indicator.
+    pub fn active_editors(&self) -> Vec<PeerId> {
+        self.remote_participants
+            .values()
+            .filter(|participant| participant.is_active())
+            .map(|participant| participant.peer_id)
+            .collect()
+    }
+
+    /// Tells the rest of the room we're actively editing right now, for
+    /// [`RemoteParticipant::is_active`]/[`Self::active_editors`] on their end. Cheap and
+    /// unpersisted by design - callers (e.g. the editor, on a debounce) can call this as often
+    /// as they like; a dropped ping just means the indicator decays a little early.
+    pub fn report_activity(&self) {
+        if self.status.is_offline() {
+            return;
+        }
+        self.client
+            .send(proto::UpdateParticipantActivity { room_id: self.id })
+            .log_err();
+    }
+
+    async fn handle_participant_activity(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateParticipantActivity>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let peer_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            if let Some(participant) = this
+                .remote_participants
+                .values_mut()
+                .find(|participant| participant.peer_id == peer_id)
+            {
+                participant.note_activity();
+                cx.notify();
+            }
+        })
+    }
+
+    /// How many remote participants are currently in the project with `project_id`, for a "3
+    /// people editing" badge. `0` if nobody is there (including if the project doesn't exist).
+    pub fn project_occupancy(&self, project_id: u64) -> usize {
+        self.project_occupancy
+            .get(&project_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Whether collaborators other than the host can edit the shared project with `project_id`.
+    /// `None` if no participant (including ourselves) is currently sharing that project. See
+    /// [`ProjectAccess`].
+    pub fn project_access(&self, project_id: u64) -> Option<ProjectAccess> {
+        self.local_participant
+            .projects
+            .iter()
+            .chain(
+                self.remote_participants
+                    .values()
+                    .flat_map(|participant| &participant.projects),
+            )
+            .find(|project| project.id == project_id)
+            .map(|project| ProjectAccess::from_read_only(project.read_only))
+    }
+
+    /// Resolves `peer_id`'s location to an actual [`Project`] handle this client has open,
+    /// rather than just the wire-level project id carried by [`ParticipantLocation`]. Returns
+    /// `None` if `peer_id` isn't a participant in this room at all.
+    pub fn resolved_location(
+        &self,
+        peer_id: proto::PeerId,
+        cx: &AppContext,
+    ) -> Option<ResolvedLocation> {
+        let participant = self
+            .remote_participants
+            .values()
+            .find(|participant| participant.peer_id == peer_id)?;
+        Some(match participant.location {
+            ParticipantLocation::SharedProject { project_id } => self
+                .joined_projects
+                .iter()
+                .chain(self.shared_projects.iter())
+                .find_map(|project| {
+                    let project = project.upgrade()?;
+                    (project.read(cx).remote_id() == Some(project_id)).then_some(project)
+                })
+                .map(ResolvedLocation::SharedProject)
+                .unwrap_or(ResolvedLocation::UnknownProject),
+            ParticipantLocation::UnsharedProject => ResolvedLocation::UnsharedProject,
+            ParticipantLocation::External => ResolvedLocation::External,
+        })
+    }
+
+    /// Groups remote participants by the coarse kind of their location, e.g. for a presence
+    /// sidebar that wants to show who's in a shared project separately from who's elsewhere.
+    pub fn participants_by_location_kind(&self, kind: LocationKind) -> Vec<PeerId> {
+        self.remote_participants
+            .values()
+            .filter(|participant| participant.location.kind() == kind)
+            .map(|participant| participant.peer_id)
+            .collect()
+    }
+
+    /// The buffer/file `peer_id` currently has open, if they're in a shared project and their
+    /// client reported one. Lets "follow" jump straight to the file they're looking at, not just
+    /// the project. See [`RemoteParticipant::open_path`].
+    pub fn peer_open_path(&self, peer_id: PeerId) -> Option<ProjectPath> {
+        self.remote_participants
+            .values()
+            .find(|participant| participant.peer_id == peer_id)
+            .and_then(|participant| participant.open_path.clone())
+    }
+
+    /// Where within [`Self::peer_open_path`] `peer_id`'s cursor/selection currently is, if their
+    /// client reported one. See [`RemoteParticipant::open_anchor`].
+    pub fn peer_open_anchor(&self, peer_id: PeerId) -> Option<ViewAnchor> {
+        self.remote_participants
+            .values()
+            .find(|participant| participant.peer_id == peer_id)
+            .and_then(|participant| participant.open_anchor)
+    }
+
+    /// Whether `peer_id` joined in a listen-only observer capacity. See
+    /// [`RemoteParticipant::is_observer`].
+    pub fn peer_is_observer(&self, peer_id: PeerId) -> bool {
+        self.remote_participants
+            .values()
+            .find(|participant| participant.peer_id == peer_id)
+            .map_or(false, |participant| participant.is_observer)
+    }
+
+    /// See `location_broadcasts_sent`.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn location_broadcasts_sent_for_test(&self) -> usize {
+        self.location_broadcasts_sent
+    }
+
+    /// Join/leave history for this room, oldest first, bounded to the most recent
+    /// [`AUDIT_LOG_CAPACITY`] entries. Answers "when did Alice join and leave."
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        &self.audit_log
+    }
+
+    fn push_audit_entry(&mut self, user_id: u64, kind: AuditEventKind) {
+        self.audit_log.push(AuditEntry {
+            user_id,
+            kind,
+            at: Instant::now(),
+        });
+        if self.audit_log.len() > AUDIT_LOG_CAPACITY {
+            self.audit_log.remove(0);
+        }
+    }
+
+    /// Cheap fold over the roster, for a session-health widget that wants a quick "how's this
+    /// call going" summary. Everyone currently lands in `unknown`; see [`ConnectionQuality`].
+    pub fn connection_summary(&self) -> ConnectionSummary {
+        let mut summary = ConnectionSummary::default();
+        for participant in self.remote_participants.values() {
+            match participant.connection_quality {
+                ConnectionQuality::Good => summary.good += 1,
+                ConnectionQuality::Fair => summary.fair += 1,
+                ConnectionQuality::Poor => summary.poor += 1,
+                ConnectionQuality::Unknown => summary.unknown += 1,
+            }
+        }
+        summary
+    }
+
+    /// Groups remote participants by their self-reported [`RemoteParticipant::region`], for a
+    /// latency-explainer widget that wants to show e.g. "3 in us-east, 1 in eu-west".
+    /// Participants whose server didn't report a region are grouped under `None`.
+    pub fn participants_by_region(&self) -> HashMap<Option<String>, Vec<PeerId>> {
+        let mut groups: HashMap<Option<String>, Vec<PeerId>> = HashMap::default();
+        for participant in self.remote_participants.values() {
+            groups
+                .entry(participant.region.clone())
+                .or_default()
+                .push(participant.peer_id);
+        }
+        groups
+    }
+
+    /// The roster as of the moment the room went offline, for the reconnect UI to gray out
+    /// rather than showing an empty room. Survives the `remote_participants.clear()` in
+    /// [`Room::clear_state`]; only cleared on an explicit, user-initiated leave.
+    pub fn last_known_participants(&self) -> &[RemoteParticipant] {
+        &self.last_known_participants
+    }
+
+    /// Drops any participant whose `last_seen` epoch is more than `older_than` updates stale.
+    /// This is a safety valve for server bugs that leave a "ghost" participant in the roster
+    /// after they should have been removed.
+    pub fn prune_stale_participants(&mut self, older_than: u64, cx: &mut ModelContext<Self>) {
+        let current_epoch = self.update_epoch;
+        let stale_user_ids = self
+            .remote_participants
+            .iter()
+            .filter(|(_, participant)| {
+                current_epoch.saturating_sub(participant.last_seen) > older_than
+            })
+            .map(|(user_id, _)| *user_id)
+            .collect::<Vec<_>>();
+
+        for user_id in stale_user_ids {
+            self.remote_participants.remove(&user_id);
+            self.participant_handles.remove(&user_id);
+            self.participant_user_ids.remove(&user_id);
+        }
+
+        cx.notify();
+    }
+
+    /// Recomputes every remote participant's [`Presence`] from how far their `last_seen` epoch
+    /// has fallen behind the current one: more than `stale_after` updates behind flips them to
+    /// [`Presence::Away`], otherwise they're [`Presence::Active`]. Unlike
+    /// [`Room::prune_stale_participants`], this never removes anyone - it just changes how they're
+    /// displayed, so a participant who reappears later goes back to `Active` on their next update.
+    pub fn refresh_presence(&mut self, stale_after: u64, cx: &mut ModelContext<Self>) {
+        let current_epoch = self.update_epoch;
+        for participant in self.remote_participants.values_mut() {
+            let is_stale = current_epoch.saturating_sub(participant.last_seen) > stale_after;
+            participant.presence = if is_stale {
+                Presence::Away
+            } else {
+                Presence::Active
+            };
+        }
+        cx.notify();
+    }
+
+    /// The peer ids of every remote participant currently marked [`Presence::Away`] by the most
+    /// recent [`Room::refresh_presence`] call.
+    pub fn away_participants(&self) -> Vec<PeerId> {
+        self.remote_participants
+            .values()
+            .filter(|participant| participant.presence == Presence::Away)
+            .map(|participant| participant.peer_id)
+            .collect()
+    }
+
+    /// Remote participants ordered for a speaker-focused layout: whoever spoke most recently
+    /// (per `last_spoke_sequence`, bumped on every [`RoomUpdate::ActiveSpeakersChanged`]) comes
+    /// first. Participants who haven't spoken - or whose most recent turn ties with someone
+    /// else's - decay to join order, via `remote_participant_order`.
+    pub fn participants_by_recent_speech(&self) -> Vec<PeerId> {
+        let join_order: HashMap<u64, usize> = self
+            .remote_participant_order
+            .iter()
+            .enumerate()
+            .map(|(index, user_id)| (*user_id, index))
+            .collect();
+        let mut participants: Vec<_> = self.remote_participants.values().collect();
+        participants.sort_by(|a, b| {
+            b.last_spoke_sequence.cmp(&a.last_spoke_sequence).then_with(|| {
+                let a_join_order = join_order.get(&a.user.id).copied().unwrap_or(usize::MAX);
+                let b_join_order = join_order.get(&b.user.id).copied().unwrap_or(usize::MAX);
+                a_join_order.cmp(&b_join_order)
+            })
+        });
+        participants.into_iter().map(|p| p.peer_id).collect()
+    }
+
+    /// Like `remote_participants`, but also synthesizes an entry for the local participant.
+    /// `apply_room_update` always filters the local user out of `remote_participants`; this is
+    /// for debug/admin views that want to see themselves in the roster.
+    pub fn all_participants_including_self(&self, cx: &AppContext) -> Vec<RemoteParticipant> {
+        let mut participants = self.remote_participants.values().cloned().collect::<Vec<_>>();
+        if let Some(user) = self.user_store.read(cx).current_user() {
+            let location = self
+                .local_participant
+                .active_project
+                .as_ref()
+                .and_then(|project| project.upgrade())
+                .map_or(ParticipantLocation::External, |project| {
+                    match project.read(cx).remote_id() {
+                        Some(project_id) => ParticipantLocation::SharedProject { project_id },
+                        None => ParticipantLocation::UnsharedProject,
+                    }
+                });
+            participants.push(RemoteParticipant {
+                user,
+                peer_id: self.client.peer_id().unwrap_or_default(),
+                role: self.local_participant.role,
+                projects: self.local_participant.projects.clone(),
+                location,
+                participant_index: ParticipantIndex(0),
+                client_kind: ClientKind::Unknown,
+                capabilities: Capabilities::all(),
+                connection_quality: ConnectionQuality::default(),
+                region: None,
+                open_path: self.local_open_path.clone(),
+                open_anchor: self.local_open_anchor,
+                is_observer: self.local_participant.is_observer,
+                presence: Presence::Active,
+                last_seen: self.update_epoch,
+                muted: self.is_muted(),
+                speaking: self.is_speaking(),
+                mic_state: if self.is_muted() {
+                    proto::MicState::Muted
+                } else {
+                    proto::MicState::Active
+                },
+                network_type: proto::NetworkType::Unknown,
+                last_spoke_sequence: None,
+                video_enabled: self.is_video_enabled(),
+                video_tracks: Default::default(),
+                audio_tracks: Default::default(),
+                last_active_at: None,
+            });
+        }
+        participants
+    }
+
+    /// Filters remote participants without allocating an intermediate collection, e.g. for
+    /// "screen sharing and unmuted" style queries.
+    pub fn participants_where<F>(
+        &self,
+        mut pred: F,
+    ) -> impl Iterator<Item = (&u64, &RemoteParticipant)>
+    where
+        F: FnMut(&RemoteParticipant) -> bool,
+    {
+        self.remote_participants
+            .iter()
+            .filter(move |(_, participant)| pred(participant))
+    }
+
+    /// Peers with video currently enabled, e.g. for grid-layout decisions. Ordered by user id,
+    /// matching the stable ordering used by [`Room::remote_participants`].
+    pub fn video_participants(&self) -> Vec<PeerId> {
+        self.participants_where(|participant| participant.video_enabled)
+            .map(|(_, participant)| participant.peer_id)
+            .collect()
+    }
+
+    /// Asks the SFU for a different resolution of `peer_id`'s video - e.g.
+    /// [`VideoQuality::Low`] for a thumbnail, [`VideoQuality::Full`] once they're focused.
+    /// Rejects if `peer_id` isn't currently publishing video.
+    pub fn request_video_quality(
+        &mut self,
+        peer_id: PeerId,
+        quality: VideoQuality,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let has_video = self
+            .remote_participants
+            .values()
+            .any(|participant| participant.peer_id == peer_id && participant.video_enabled);
+        if !has_video {
+            return Task::ready(Err(anyhow!(
+                "peer {:?} has no video to request a quality for",
+                peer_id
+            )));
+        }
+
+        self.requested_video_qualities.insert(peer_id, quality);
+        cx.notify();
+        Task::ready(Ok(()))
+    }
+
+    /// The quality last requested via [`Self::request_video_quality`] for `peer_id`, if any.
+    pub fn requested_video_quality(&self, peer_id: PeerId) -> Option<VideoQuality> {
+        self.requested_video_qualities.get(&peer_id).copied()
+    }
+
+    pub fn remote_participant_for_peer_id(&self, peer_id: PeerId) -> Option<&RemoteParticipant> {
+        self.remote_participants
+            .values()
+            .find(|p| p.peer_id == peer_id)
+    }
+
+    /// Searches the roster for participants whose display name or user id contains `query`
+    /// (case-insensitive), for a participant-search box. Results come back in the same order as
+    /// [`Self::remote_participants`] - stable across repeated searches as the user types, rather
+    /// than reshuffling by match quality.
+    pub fn find_participants(&self, query: &str) -> Vec<PeerId> {
+        let query = query.to_lowercase();
+        self.remote_participants
+            .values()
+            .filter(|participant| {
+                participant
+                    .user
+                    .github_login
+                    .to_lowercase()
+                    .contains(&query)
+                    || participant.user.id.to_string().contains(&query)
+            })
+            .map(|participant| participant.peer_id)
+            .collect()
+    }
+
+    /// The screen-share track currently being rendered for `peer_id`, if they're sharing their
+    /// screen. `video_tracks` only ever carries a screen share in this product - there's no
+    /// webcam feature - so this is just a named way to reach the one that matters, cleared
+    /// automatically when the peer stops sharing ([`RoomUpdate::UnsubscribedFromRemoteVideoTrack`])
+    /// or leaves (their [`RemoteParticipant`] is dropped).
+    pub fn screen_share_track(&self, peer_id: PeerId) -> Option<Arc<RemoteVideoTrack>> {
+        self.remote_participant_for_peer_id(peer_id)?
+            .video_tracks
+            .values()
+            .next()
+            .cloned()
+    }
+
+    /// The fixed palette [`Self::participant_color`] assigns participants from.
+    const PARTICIPANT_COLOR_PALETTE: [Hsla; 8] = [
+        Hsla { h: 0. / 360., s: 0.65, l: 0.55, a: 1.0 },
+        Hsla { h: 45. / 360., s: 0.65, l: 0.55, a: 1.0 },
+        Hsla { h: 90. / 360., s: 0.55, l: 0.45, a: 1.0 },
+        Hsla { h: 135. / 360., s: 0.55, l: 0.45, a: 1.0 },
+        Hsla { h: 180. / 360., s: 0.55, l: 0.45, a: 1.0 },
+        Hsla { h: 225. / 360., s: 0.55, l: 0.55, a: 1.0 },
+        Hsla { h: 270. / 360., s: 0.55, l: 0.55, a: 1.0 },
+        Hsla { h: 315. / 360., s: 0.6, l: 0.55, a: 1.0 },
+    ];
+
+    /// A deterministic color for `peer_id`'s participant, derived from a stable hash of their
+    /// `user_id` so the same person gets the same color across sessions and clients - unlike
+    /// [`client::ParticipantIndex`], which is reassigned on every join and isn't stable across
+    /// rejoins. Colors are drawn from [`Self::PARTICIPANT_COLOR_PALETTE`]; if two participants
+    /// currently in the room hash to the same slot, the lower `user_id` keeps it and the other
+    /// is bumped to the next free slot, so no two people in the room look the same at once.
+    /// Returns `None` if `peer_id` isn't a current remote participant.
+    pub fn participant_color(&self, peer_id: PeerId) -> Option<Hsla> {
+        let mut participants = self.remote_participants.values().collect::<Vec<_>>();
+        participants.sort_by_key(|participant| participant.user.id);
+
+        let palette = Self::PARTICIPANT_COLOR_PALETTE;
+        let mut taken = [false; Self::PARTICIPANT_COLOR_PALETTE.len()];
+        let mut color = None;
+        for participant in participants {
+            let mut index = Self::color_palette_index(participant.user.id);
+            while taken[index] {
+                index = (index + 1) % palette.len();
+            }
+            taken[index] = true;
+            if participant.peer_id == peer_id {
+                color = Some(palette[index]);
+            }
+        }
+        color
+    }
+
+    fn color_palette_index(user_id: u64) -> usize {
+        let mut hasher = FxHasher::default();
+        user_id.hash(&mut hasher);
+        (hasher.finish() % Self::PARTICIPANT_COLOR_PALETTE.len() as u64) as usize
+    }
+
+    /// Streams the set of project ids shared by the given peer, e.g. for a "files shared by
+    /// Alice" panel. Emits once immediately with the peer's current projects, then again on
+    /// every subsequent change. The stream closes when the peer leaves the room.
+    pub fn observe_peer_projects(
+        &self,
+        peer_id: PeerId,
+        cx: &mut ModelContext<Self>,
+    ) -> mpsc::UnboundedReceiver<Vec<u64>> {
+        let (tx, rx) = mpsc::unbounded();
+        if let Some(handle) = self
+            .remote_participant_for_peer_id(peer_id)
+            .and_then(|participant| self.participant_handles.get(&participant.user.id))
+            .cloned()
+        {
+            fn project_ids(handle: &Model<RemoteParticipant>, cx: &AppContext) -> Vec<u64> {
+                handle.read(cx).projects.iter().map(|p| p.id).collect()
+            }
+
+            let mut update_tx = tx.clone();
+            update_tx.unbounded_send(project_ids(&handle, cx)).ok();
+            cx.observe(&handle, move |_, handle, cx| {
+                update_tx.unbounded_send(project_ids(&handle, cx)).ok();
+            })
+            .detach();
+            cx.observe_release(&handle, move |_, _, _| drop(tx))
+                .detach();
+        }
+        rx
+    }
+
+    /// Streams the given peer's mute state, e.g. for a per-tile mic indicator. Emits once
+    /// immediately with their current state, then again only when it actually changes - unlike
+    /// [`Self::observe_peer_projects`], repeated participant updates that leave `muted` alone
+    /// produce no further items. The stream closes when the peer leaves the room.
+    pub fn observe_mute(
+        &self,
+        peer_id: PeerId,
+        cx: &mut ModelContext<Self>,
+    ) -> mpsc::UnboundedReceiver<bool> {
+        let (tx, rx) = mpsc::unbounded();
+        if let Some(handle) = self
+            .remote_participant_for_peer_id(peer_id)
+            .and_then(|participant| self.participant_handles.get(&participant.user.id))
+            .cloned()
+        {
+            let mut last_muted = handle.read(cx).muted;
+            let mut update_tx = tx.clone();
+            update_tx.unbounded_send(last_muted).ok();
+            cx.observe(&handle, move |_, handle, cx| {
+                let muted = handle.read(cx).muted;
+                if muted != last_muted {
+                    last_muted = muted;
+                    update_tx.unbounded_send(muted).ok();
+                }
+            })
+            .detach();
+            cx.observe_release(&handle, move |_, _, _| drop(tx))
+                .detach();
+        }
+        rx
+    }
+
+    /// Returns an entity handle that updates (and notifies its own observers) whenever this
+    /// single participant changes, without requiring a subscription to the whole room.
+    pub fn participant_handle(&self, peer_id: PeerId) -> Option<Model<RemoteParticipant>> {
+        let user_id = self
+            .remote_participants
+            .iter()
+            .find(|(_, participant)| participant.peer_id == peer_id)
+            .map(|(user_id, _)| *user_id)?;
+        self.participant_handles.get(&user_id).cloned()
+    }
+
+    pub fn role_for_user(&self, user_id: u64) -> Option<proto::ChannelRole> {
+        self.remote_participants
+            .get(&user_id)
+            .map(|participant| participant.role)
+    }
+
+    pub fn contains_guests(&self) -> bool {
+        self.local_participant.role == proto::ChannelRole::Guest
             || self
                 .remote_participants
                 .values()
@@ -643,20 +2296,362 @@ impl Room {
         })
     }
 
+    /// Grants `user_id` co-host privileges, letting them moderate the room alongside the host.
+    /// Only the host (the room's admin) can promote someone.
+    pub fn promote_to_cohost(&mut self, user_id: u64, cx: &ModelContext<Self>) -> Task<Result<()>> {
+        if !self.local_participant_is_admin() {
+            return Task::ready(Err(anyhow!("only the host can promote a co-host")));
+        }
+        self.set_participant_role(user_id, proto::ChannelRole::CoHost, cx)
+    }
+
+    /// Strips `user_id` of co-host privileges, returning them to a regular member. Only the
+    /// host can demote a co-host.
+    pub fn demote(&mut self, user_id: u64, cx: &ModelContext<Self>) -> Task<Result<()>> {
+        if !self.local_participant_is_admin() {
+            return Task::ready(Err(anyhow!("only the host can demote a co-host")));
+        }
+        self.set_participant_role(user_id, proto::ChannelRole::Member, cx)
+    }
+
+    /// Kicks `user_id` out of the room. Available to the host and to co-hosts.
+    pub fn remove_participant(
+        &mut self,
+        user_id: u64,
+        cx: &ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if !self.can_moderate() {
+            return Task::ready(Err(anyhow!(
+                "only the host or a co-host can remove a participant"
+            )));
+        }
+        let client = self.client.clone();
+        let room_id = self.id;
+        cx.spawn(|_, _| async move {
+            client
+                .request(proto::RemoveRoomParticipant { room_id, user_id })
+                .await
+                .map(|_| ())
+        })
+    }
+
+    /// Forces `user_id`'s microphone off (or restores it), without changing their role.
+    /// Available to the host and to co-hosts.
+    pub fn mute_participant_remotely(
+        &mut self,
+        user_id: u64,
+        muted: bool,
+        cx: &ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if !self.can_moderate() {
+            return Task::ready(Err(anyhow!(
+                "only the host or a co-host can mute a participant"
+            )));
+        }
+        let client = self.client.clone();
+        let room_id = self.id;
+        cx.spawn(|_, _| async move {
+            client
+                .request(proto::MuteRoomParticipant {
+                    room_id,
+                    user_id,
+                    muted,
+                })
+                .await
+                .map(|_| ())
+        })
+    }
+
+    /// Forces every remote participant's microphone off, except those listed in `except` - e.g.
+    /// for a host silencing a room before a presentation. Available to the host and co-hosts.
+    pub fn mute_all(&mut self, except: &[PeerId], cx: &ModelContext<Self>) -> Task<Result<()>> {
+        if !self.can_moderate() {
+            return Task::ready(Err(anyhow!(
+                "only the host or a co-host can mute all participants"
+            )));
+        }
+        let client = self.client.clone();
+        let room_id = self.id;
+        let user_ids = self
+            .remote_participants
+            .values()
+            .filter(|participant| !except.contains(&participant.peer_id))
+            .map(|participant| participant.user.id)
+            .collect::<Vec<_>>();
+        cx.spawn(|_, _| async move {
+            let tasks = user_ids.into_iter().map(|user_id| {
+                let client = client.clone();
+                async move {
+                    client
+                        .request(proto::MuteRoomParticipant {
+                            room_id,
+                            user_id,
+                            muted: true,
+                        })
+                        .await
+                        .map(|_| ())
+                }
+            });
+            futures::future::try_join_all(tasks).await?;
+            Ok(())
+        })
+    }
+
+    /// Asks `user_id` to mute themselves, without forcing it the way
+    /// [`Room::mute_participant_remotely`] does. Available to the host and co-hosts.
+    pub fn request_mute(&mut self, user_id: u64, cx: &ModelContext<Self>) -> Task<Result<()>> {
+        if !self.can_moderate() {
+            return Task::ready(Err(anyhow!(
+                "only the host or a co-host can request a participant mute"
+            )));
+        }
+        let client = self.client.clone();
+        let room_id = self.id;
+        cx.spawn(|_, _| async move {
+            client
+                .request(proto::RequestMuteRoomParticipant { room_id, user_id })
+                .await
+                .map(|_| ())
+        })
+    }
+
     pub fn pending_participants(&self) -> &[Arc<User>] {
         &self.pending_participants
     }
 
+    /// A cheap, one-shot summary of [`Room::remote_participants`] and
+    /// [`Room::pending_participants`], for UI that wants "3 in call, 1 ringing" without making
+    /// three separate calls.
+    pub fn counts(&self) -> RoomCounts {
+        RoomCounts {
+            joined: self.remote_participants.len(),
+            pending: self.pending_participants.len(),
+            local: 1,
+        }
+    }
+
+    /// Cancels every outstanding invite to a pending participant, clearing the list
+    /// optimistically. If a cancellation fails to reach the server, the next room update
+    /// will reconcile `pending_participants` with the server's view.
+    pub fn decline_all_calls(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let client = self.client.clone();
+        let room_id = self.id;
+        let called_user_ids = mem::take(&mut self.pending_participants)
+            .into_iter()
+            .map(|user| user.id)
+            .collect::<Vec<_>>();
+        self.pending_participant_expirations.clear();
+        cx.notify();
+        cx.background_executor().spawn(async move {
+            for called_user_id in called_user_ids {
+                client
+                    .request(proto::CancelCall {
+                        room_id,
+                        called_user_id,
+                    })
+                    .await
+                    .log_err();
+            }
+            Ok(())
+        })
+    }
+
+    /// Resolves a batch of outstanding invites at once - for a host dealing with a flood of
+    /// pending participants who wants to keep some ringing and cancel others, without nuking
+    /// the whole list via [`Room::decline_all_calls`]. Declined participants are removed from
+    /// [`Room::pending_participants`] optimistically; if a cancellation never reaches the
+    /// server, the next room update reconciles the list with the server's view.
+    pub fn respond_to_calls(
+        &mut self,
+        decisions: HashMap<u64, CallDecision>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<CallResponseSummary>> {
+        let mut accepted = 0;
+        let to_decline = decisions
+            .into_iter()
+            .filter_map(|(user_id, decision)| match decision {
+                CallDecision::Accept => {
+                    accepted += 1;
+                    None
+                }
+                CallDecision::Decline => Some(user_id),
+            })
+            .collect::<HashSet<_>>();
+
+        let client = self.client.clone();
+        let room_id = self.id;
+        self.pending_participants
+            .retain(|user| !to_decline.contains(&user.id));
+        self.pending_participant_expirations
+            .retain(|user_id, _| !to_decline.contains(user_id));
+        cx.notify();
+        cx.background_executor().spawn(async move {
+            let mut declined = 0;
+            let mut failed = 0;
+            for called_user_id in to_decline {
+                match client
+                    .request(proto::CancelCall {
+                        room_id,
+                        called_user_id,
+                    })
+                    .await
+                {
+                    Ok(_) => declined += 1,
+                    Err(_) => failed += 1,
+                }
+            }
+            Ok(CallResponseSummary {
+                accepted,
+                declined,
+                failed,
+            })
+        })
+    }
+
+    /// (Re)schedules local auto-expiration for each of `self.pending_participants`, so an
+    /// invite that's still unanswered after [`PENDING_PARTICIPANT_TIMEOUT`] gets canceled even
+    /// if the server never gets around to it. Called whenever the pending list is replaced; a
+    /// participant who reappears in a later update (i.e. whose pending status was refreshed)
+    /// keeps its existing timer rather than getting a new one.
+    fn reschedule_pending_participant_expirations(&mut self, cx: &mut ModelContext<Self>) {
+        let pending_user_ids = self
+            .pending_participants
+            .iter()
+            .map(|user| user.id)
+            .collect::<HashSet<_>>();
+        self.pending_participant_expirations
+            .retain(|user_id, _| pending_user_ids.contains(user_id));
+        for user_id in pending_user_ids {
+            self.pending_participant_expirations
+                .entry(user_id)
+                .or_insert_with(|| {
+                    cx.spawn(move |this, mut cx| async move {
+                        cx.background_executor()
+                            .timer(PENDING_PARTICIPANT_TIMEOUT)
+                            .await;
+                        this.update(&mut cx, |this, cx| {
+                            this.expire_pending_participant(user_id, cx)
+                        })
+                        .ok();
+                    })
+                });
+        }
+    }
+
+    /// Cancels `user_id`'s invite locally and tells the server, as if [`Room::decline_all_calls`]
+    /// had been called for just this one participant.
+    fn expire_pending_participant(&mut self, user_id: u64, cx: &mut ModelContext<Self>) {
+        self.pending_participant_expirations.remove(&user_id);
+        let Some(ix) = self
+            .pending_participants
+            .iter()
+            .position(|user| user.id == user_id)
+        else {
+            return;
+        };
+        self.pending_participants.remove(ix);
+        cx.notify();
+        self.emit_event(Event::PendingParticipantExpired { user_id }, cx);
+
+        let client = self.client.clone();
+        let room_id = self.id;
+        cx.background_executor()
+            .spawn(async move {
+                client
+                    .request(proto::CancelCall {
+                        room_id,
+                        called_user_id: user_id,
+                    })
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+
     pub fn contains_participant(&self, user_id: u64) -> bool {
         self.participant_user_ids.contains(&user_id)
     }
 
+    /// Whether the remote participant at `peer_id` supports `capability`, for hiding UI
+    /// affordances (e.g. "request screen share") that peer couldn't honor. `false` for an unknown
+    /// `peer_id`, same as a participant with no capabilities at all.
+    pub fn peer_supports(&self, peer_id: PeerId, capability: Capabilities) -> bool {
+        self.remote_participants
+            .values()
+            .find(|participant| participant.peer_id == peer_id)
+            .is_some_and(|participant| participant.capabilities.contains(capability))
+    }
+
     pub fn followers_for(&self, leader_id: PeerId, project_id: u64) -> &[PeerId] {
         self.follows_by_leader_id_project_id
             .get(&(leader_id, project_id))
             .map_or(&[], |v| v.as_slice())
     }
 
+    /// Remote participants currently following the local user, across all of their shared
+    /// projects, for a "2 people are following you" awareness indicator. Empty if the server
+    /// hasn't relayed any follow relationships naming the local user as leader, or if we don't
+    /// know our own peer id yet. See [`Event::FollowerAdded`]/[`Event::FollowerRemoved`].
+    pub fn followers(&self) -> Vec<PeerId> {
+        let Some(local_peer_id) = self.client.peer_id() else {
+            return Vec::new();
+        };
+        self.followers_of(local_peer_id)
+    }
+
+    fn followers_of(&self, leader_id: PeerId) -> Vec<PeerId> {
+        let mut followers = Vec::new();
+        for ((candidate_leader_id, _project_id), follower_ids) in &self.follows_by_leader_id_project_id
+        {
+            if *candidate_leader_id == leader_id {
+                for follower_id in follower_ids {
+                    if !followers.contains(follower_id) {
+                        followers.push(*follower_id);
+                    }
+                }
+            }
+        }
+        followers
+    }
+
+    /// Starts following `leader_id` for cross-pane navigation. This is purely local intent - it
+    /// survives a disconnect/reconnect (see [`Room::clear_state`]) so that if the leader is
+    /// still around once the room comes back online, following resumes without the user having
+    /// to ask again. If the leader doesn't come back within [`FOLLOW_TARGET_TIMEOUT`],
+    /// [`Event::FollowTargetLost`] fires and following is canceled.
+    pub fn follow(&mut self, leader_id: u64, cx: &mut ModelContext<Self>) {
+        self.local_follow_target = Some(leader_id);
+        self.follow_target_timeout.take();
+        let peer_id = self.remote_participants.get(&leader_id).map(|p| p.peer_id);
+        self.emit_event(Event::FollowingChanged { leader_id: peer_id }, cx);
+    }
+
+    /// Stops following whoever [`Room::follow_target`] currently points to, if anyone.
+    pub fn unfollow(&mut self, cx: &mut ModelContext<Self>) {
+        if self.local_follow_target.take().is_some() {
+            self.follow_target_timeout.take();
+            self.emit_event(Event::FollowingChanged { leader_id: None }, cx);
+        }
+    }
+
+    /// The peer id of whoever the local user is currently following, if any and if they're
+    /// currently present in the roster.
+    pub fn follow_target(&self) -> Option<PeerId> {
+        let leader_id = self.local_follow_target?;
+        self.remote_participants.get(&leader_id).map(|p| p.peer_id)
+    }
+
+    /// Gives up on the followed leader returning after a disconnect. Called once
+    /// [`FOLLOW_TARGET_TIMEOUT`] elapses without them reappearing in the roster; a no-op if the
+    /// user already unfollowed or the leader already came back.
+    fn expire_follow_target(&mut self, leader_id: u64, cx: &mut ModelContext<Self>) {
+        self.follow_target_timeout.take();
+        if self.local_follow_target == Some(leader_id) {
+            self.local_follow_target = None;
+            self.emit_event(Event::FollowTargetLost { leader_id }, cx);
+        }
+    }
+
     /// Returns the most 'active' projects, defined as most people in the project
     pub fn most_active_project(&self, cx: &AppContext) -> Option<(u64, u64)> {
         let mut project_hosts_and_guest_counts = HashMap::<u64, (Option<u64>, u32)>::default();
@@ -699,11 +2694,121 @@ impl Room {
         envelope: TypedEnvelope<proto::RoomUpdated>,
         mut cx: AsyncAppContext,
     ) -> Result<()> {
-        let room = envelope
+        if let Some(room) = envelope.payload.room {
+            if let Err(error) = this.update(&mut cx, |this, cx| this.apply_room_update(room, cx))?
+            {
+                log::error!("applying room update failed, resyncing: {error:?}");
+                this.update(&mut cx, |this, cx| this.resync(cx))?.await?;
+            }
+            return Ok(());
+        }
+
+        // The server can send a delta that only touches `pending_participants` (e.g. to
+        // announce a new incoming call) without resending the whole room.
+        this.update(&mut cx, |this, cx| {
+            this.apply_pending_participants_update(envelope.payload.pending_participants, cx)
+        })?
+        .await
+    }
+
+    async fn handle_participant_left(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ParticipantLeft>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let peer_id = envelope
             .payload
-            .room
-            .ok_or_else(|| anyhow!("invalid room"))?;
-        this.update(&mut cx, |this, cx| this.apply_room_update(room, cx))?
+            .peer_id
+            .ok_or_else(|| anyhow!("invalid peer id"))?;
+        this.update(&mut cx, |this, cx| {
+            this.emit_event(
+                Event::ParticipantFarewell {
+                    peer_id,
+                    message: envelope.payload.farewell_message,
+                    reason: envelope.payload.leave_reason(),
+                },
+                cx,
+            );
+        })
+    }
+
+    /// Tells us whether the host (or a co-host) has force-muted us, via a
+    /// [`proto::MuteRoomParticipant`] targeting the local user. Forcibly mutes right away when
+    /// set; lifting it just clears the flag, leaving the user muted until they unmute themselves.
+    async fn handle_force_mute(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ForceMute>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            this.force_muted = envelope.payload.muted;
+            if this.force_muted {
+                if let Some(live_kit) = this.live_kit.as_mut() {
+                    live_kit.muted_by_user = true;
+                }
+                this.set_mute(true, cx);
+            }
+            cx.notify();
+        })
+    }
+
+    /// Tells us the host or a co-host is asking us to mute, via [`proto::RequestMute`]. Unlike
+    /// [`Room::handle_force_mute`], this doesn't mute anything by itself - it just surfaces
+    /// [`Event::MuteRequested`] for the UI to act on.
+    async fn handle_request_mute(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::RequestMute>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let by = envelope
+            .payload
+            .requested_by
+            .ok_or_else(|| anyhow!("invalid peer id"))?;
+        this.update(&mut cx, |this, cx| {
+            this.emit_event(Event::MuteRequested { by }, cx);
+        })
+    }
+
+    /// Applies a delta that only touches the room's pending (not-yet-answered) participants,
+    /// leaving everything else - remote participants, followers, the local participant's role -
+    /// untouched. This is what [`Room::handle_room_updated`] falls back to when the server sends
+    /// a [`proto::RoomUpdated`] with no `room` field.
+    fn apply_pending_participants_update(
+        &mut self,
+        pending_participants: Vec<proto::PendingParticipant>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let pending_participant_user_ids = pending_participants
+            .iter()
+            .map(|participant| participant.user_id)
+            .collect::<Vec<_>>();
+        let pending_participants = self
+            .user_store
+            .update(cx, |user_store, cx| {
+                user_store.get_users(pending_participant_user_ids, cx)
+            });
+
+        cx.spawn(|this, mut cx| async move {
+            let pending_participants = pending_participants.await?;
+            this.update(&mut cx, |this, cx| {
+                // Drop ids for participants that were pending before this update but aren't
+                // anymore (e.g. their invite was declined or canceled), unless they're also a
+                // remote participant - this delta only ever replaces the pending set, so remote
+                // participant ids must be left alone.
+                for participant in &this.pending_participants {
+                    if !this.remote_participants.contains_key(&participant.id) {
+                        this.participant_user_ids.remove(&participant.id);
+                    }
+                }
+                this.pending_participants = pending_participants;
+                for participant in &this.pending_participants {
+                    this.participant_user_ids.insert(participant.id);
+                }
+                this.reschedule_pending_participant_expirations(cx);
+                this.room_update_completed_tx.try_send(Some(())).ok();
+                cx.notify();
+            })
+        })
     }
 
     fn apply_room_update(
@@ -711,6 +2816,15 @@ impl Room {
         mut room: proto::Room,
         cx: &mut ModelContext<Self>,
     ) -> Result<()> {
+        if room.id != self.id {
+            return Err(anyhow!(
+                "received an update for room {} while in room {}",
+                room.id,
+                self.id
+            ));
+        }
+        self.last_message_at = Instant::now();
+
         // Filter ourselves out from the room's participants.
         let local_participant_ix = room
             .participants
@@ -743,6 +2857,10 @@ impl Room {
                 futures::join!(remote_participants, pending_participants);
 
             this.update(&mut cx, |this, cx| {
+                this.update_epoch += 1;
+                let update_epoch = this.update_epoch;
+                let mut had_no_remote_participants = this.remote_participants.is_empty();
+                let mut newly_joined_peer_ids = Vec::new();
                 this.participant_user_ids.clear();
 
                 if let Some(participant) = local_participant {
@@ -781,6 +2899,13 @@ impl Room {
                         let Some(peer_id) = participant.peer_id else {
                             continue;
                         };
+                        if peer_id == PeerId::default() {
+                            log::warn!(
+                                "ignoring participant {} with an invalid (zero) peer id",
+                                participant.user_id
+                            );
+                            continue;
+                        }
                         let participant_index = ParticipantIndex(participant.participant_index);
                         this.participant_user_ids.insert(participant.user_id);
 
@@ -799,11 +2924,14 @@ impl Room {
 
                         for project in &participant.projects {
                             if !old_projects.contains(&project.id) {
-                                cx.emit(Event::RemoteProjectShared {
-                                    owner: user.clone(),
-                                    project_id: project.id,
-                                    worktree_root_names: project.worktree_root_names.clone(),
-                                });
+                                this.emit_event(
+                                    Event::RemoteProjectShared {
+                                        owner: user.clone(),
+                                        project_id: project.id,
+                                        worktree_root_names: project.worktree_root_names.clone(),
+                                    },
+                                    cx,
+                                );
                             }
                         }
 
@@ -822,30 +2950,131 @@ impl Room {
                                     false
                                 }
                             });
-                            cx.emit(Event::RemoteProjectUnshared {
-                                project_id: *unshared_project_id,
-                            });
+                            this.emit_event(
+                                Event::RemoteProjectUnshared {
+                                    project_id: *unshared_project_id,
+                                },
+                                cx,
+                            );
+
+                            if this
+                                .client
+                                .peer_id()
+                                .zip(this.follows_by_leader_id_project_id.get(&(peer_id, *unshared_project_id)))
+                                .map_or(false, |(local_peer_id, followers)| {
+                                    followers.contains(&local_peer_id)
+                                })
+                            {
+                                this.emit_event(
+                                    Event::FollowTargetLostProject {
+                                        peer_id,
+                                        project_id: *unshared_project_id,
+                                    },
+                                    cx,
+                                );
+                            }
                         }
 
                         let role = participant.role();
-                        let location = ParticipantLocation::from_proto(participant.location)
-                            .unwrap_or(ParticipantLocation::External);
+                        let is_observer = participant.is_observer;
+                        let shared_project = participant
+                            .location
+                            .as_ref()
+                            .and_then(|location| location.variant.as_ref())
+                            .and_then(|variant| match variant {
+                                proto::participant_location::Variant::SharedProject(
+                                    shared_project,
+                                ) => Some(shared_project),
+                                _ => None,
+                            });
+                        let open_path = shared_project
+                            .and_then(|shared_project| shared_project.open_path.clone())
+                            .map(ProjectPath::from_proto);
+                        let open_anchor = shared_project
+                            .and_then(|shared_project| shared_project.anchor)
+                            .map(ViewAnchor::from_proto);
+                        let location = match ParticipantLocation::from_proto(participant.location)
+                        {
+                            Ok(location) => location,
+                            Err(error) => {
+                                log::error!("failed to parse participant location: {error:?}");
+                                this.metrics.participant_parse_errors += 1;
+                                ParticipantLocation::External
+                            }
+                        };
+                        let client_kind = participant
+                            .platform
+                            .as_deref()
+                            .map(ClientKind::from_platform_str);
+                        let mic_state = participant.mic_state();
+                        let network_type = participant.network_type();
+                        let region = participant.region.clone();
                         if let Some(remote_participant) =
                             this.remote_participants.get_mut(&participant.user_id)
                         {
                             remote_participant.peer_id = peer_id;
                             remote_participant.projects = participant.projects;
                             remote_participant.participant_index = participant_index;
-                            if location != remote_participant.location
-                                || role != remote_participant.role
-                            {
+                            remote_participant.last_seen = update_epoch;
+                            remote_participant.open_path = open_path;
+                            remote_participant.open_anchor = open_anchor;
+                            remote_participant.presence = Presence::Active;
+                            remote_participant.mic_state = mic_state;
+                            remote_participant.network_type = network_type;
+                            remote_participant.region = region;
+                            if let Some(client_kind) = client_kind {
+                                remote_participant.client_kind = client_kind;
+                                remote_participant.capabilities =
+                                    Capabilities::from_client_kind(client_kind);
+                            }
+                            if location != remote_participant.location {
                                 remote_participant.location = location;
+                                this.emit_event(
+                                    Event::ParticipantLocationChanged {
+                                        participant_id: peer_id,
+                                    },
+                                    cx,
+                                );
+                            }
+                            if role != remote_participant.role {
                                 remote_participant.role = role;
-                                cx.emit(Event::ParticipantLocationChanged {
-                                    participant_id: peer_id,
-                                });
+                                this.emit_event(Event::RoleChanged { peer_id, role }, cx);
+                            }
+                            if is_observer != remote_participant.is_observer {
+                                remote_participant.is_observer = is_observer;
+                                this.emit_event(
+                                    Event::ParticipantObserverModeChanged {
+                                        participant_id: peer_id,
+                                        is_observer,
+                                    },
+                                    cx,
+                                );
                             }
                         } else {
+                            this.push_audit_entry(participant.user_id, AuditEventKind::Joined);
+                            if had_no_remote_participants {
+                                this.emit_event(
+                                    Event::FirstParticipantJoined { peer_id },
+                                    cx,
+                                );
+                                had_no_remote_participants = false;
+                            }
+                            this.emit_event(Event::ParticipantJoined { peer_id }, cx);
+                            newly_joined_peer_ids.push(peer_id);
+                            // A quick rejoin (same user, new peer id) otherwise looks just like
+                            // any other fresh join, so restore their last known location
+                            // optimistically until this or the next update reports a real one.
+                            let cached_location = this
+                                .recent_locations
+                                .remove(&participant.user_id)
+                                .filter(|(_, cached_at)| cached_at.elapsed() < RECENT_LOCATION_TTL)
+                                .map(|(cached_location, _)| cached_location);
+                            let location = if matches!(location, ParticipantLocation::External) {
+                                cached_location.unwrap_or(location)
+                            } else {
+                                location
+                            };
+                            this.remote_participant_order.push(participant.user_id);
                             this.remote_participants.insert(
                                 participant.user_id,
                                 RemoteParticipant {
@@ -855,14 +3084,39 @@ impl Room {
                                     projects: participant.projects,
                                     location,
                                     role,
+                                    client_kind: client_kind.unwrap_or_default(),
+                                    capabilities: Capabilities::from_client_kind(
+                                        client_kind.unwrap_or_default(),
+                                    ),
+                                    connection_quality: ConnectionQuality::default(),
+                                    region,
+                                    open_path,
+                                    open_anchor,
+                                    is_observer,
+                                    presence: Presence::Active,
+                                    last_seen: update_epoch,
                                     muted: true,
                                     speaking: false,
+                                    mic_state,
+                                    network_type,
+                                    last_spoke_sequence: None,
+                                    video_enabled: false,
                                     video_tracks: Default::default(),
                                     audio_tracks: Default::default(),
+                                    last_active_at: None,
                                 },
                             );
 
-                            Audio::play_sound(Sound::Joined, cx);
+                            if this.local_follow_target == Some(participant.user_id) {
+                                this.follow_target_timeout.take();
+                                this.emit_event(
+                                    Event::FollowingChanged {
+                                        leader_id: Some(peer_id),
+                                    },
+                                    cx,
+                                );
+                                this.emit_event(Event::FollowTargetMoved { leader_id: peer_id }, cx);
+                            }
 
                             if let Some(live_kit) = this.live_kit.as_ref() {
                                 let video_tracks =
@@ -895,20 +3149,83 @@ impl Room {
                                 }
                             }
                         }
-                    }
 
-                    this.remote_participants.retain(|user_id, participant| {
-                        if this.participant_user_ids.contains(user_id) {
-                            true
-                        } else {
-                            for project in &participant.projects {
-                                cx.emit(Event::RemoteProjectUnshared {
-                                    project_id: project.id,
+                        if let Some(updated) = this.remote_participants.get(&participant.user_id) {
+                            let updated = updated.clone();
+                            if let Some(handle) = this.participant_handles.get(&participant.user_id)
+                            {
+                                handle.update(cx, |participant, cx| {
+                                    *participant = updated;
+                                    cx.notify();
                                 });
+                            } else {
+                                let handle = cx.new_model(|_| updated);
+                                this.participant_handles.insert(participant.user_id, handle);
                             }
-                            false
                         }
-                    });
+                    }
+
+                    let participants_before_diff = this.remote_participants.len();
+                    let participants_removed_by_diff = this
+                        .remote_participants
+                        .keys()
+                        .filter(|user_id| !this.participant_user_ids.contains(user_id))
+                        .count();
+                    if participants_before_diff > 0
+                        && participants_removed_by_diff as f64 / participants_before_diff as f64
+                            > this.mass_removal_resync_threshold
+                    {
+                        // This diff would drop more of the roster at once than we're willing to
+                        // trust - more likely a corrupted delta than everyone actually leaving at
+                        // once. Leave the roster untouched and ask the server for a fresh snapshot
+                        // instead of applying what could be a cascading, incorrect removal.
+                        log::warn!(
+                            "diffed room update would remove {participants_removed_by_diff}/{participants_before_diff} participants at once; requesting a full resync instead",
+                        );
+                        this.participant_user_ids
+                            .extend(this.remote_participants.keys().copied());
+                        this.resync(cx).detach_and_log_err(cx);
+                    } else {
+                        this.remote_participants.retain(|user_id, participant| {
+                            if this.participant_user_ids.contains(user_id) {
+                                true
+                            } else {
+                                this.audit_log.push(AuditEntry {
+                                    user_id: *user_id,
+                                    kind: AuditEventKind::Left,
+                                    at: Instant::now(),
+                                });
+                                if this.audit_log.len() > AUDIT_LOG_CAPACITY {
+                                    this.audit_log.remove(0);
+                                }
+                                this.participant_handles.remove(user_id);
+                                this.remote_participant_order.retain(|id| id != user_id);
+                                this.recent_locations
+                                    .insert(*user_id, (participant.location, Instant::now()));
+                                for project in &participant.projects {
+                                    cx.emit(Event::RemoteProjectUnshared {
+                                        project_id: project.id,
+                                    });
+                                }
+                                false
+                            }
+                        });
+                    }
+                }
+
+                if !newly_joined_peer_ids.is_empty() {
+                    // Play the join sound at most once per update, no matter how many
+                    // participants landed in it, so a burst of joins doesn't turn into a
+                    // cacophony.
+                    Audio::play_sound(Sound::Joined, cx);
+                    if newly_joined_peer_ids.len() > 1 {
+                        this.emit_event(
+                            Event::ParticipantsJoinedBatch {
+                                peer_ids: newly_joined_peer_ids,
+                            },
+                            cx,
+                        );
+                    }
                 }
 
                 if let Some(pending_participants) = pending_participants.log_err() {
@@ -916,8 +3233,14 @@ impl Room {
                     for participant in &this.pending_participants {
                         this.participant_user_ids.insert(participant.id);
                     }
+                    this.reschedule_pending_participant_expirations(cx);
                 }
 
+                let local_peer_id = this.client.peer_id();
+                let followers_before: HashSet<PeerId> = local_peer_id
+                    .map(|local_peer_id| this.followers_of(local_peer_id).into_iter().collect())
+                    .unwrap_or_default();
+
                 this.follows_by_leader_id_project_id.clear();
                 for follower in room.followers {
                     let project_id = follower.project_id;
@@ -939,6 +3262,35 @@ impl Room {
                     }
                 }
 
+                if let Some(local_peer_id) = local_peer_id {
+                    let followers_after: HashSet<PeerId> =
+                        this.followers_of(local_peer_id).into_iter().collect();
+                    for follower_id in followers_after.difference(&followers_before) {
+                        this.emit_event(
+                            Event::FollowerAdded {
+                                follower_id: *follower_id,
+                            },
+                            cx,
+                        );
+                    }
+                    for follower_id in followers_before.difference(&followers_after) {
+                        this.emit_event(
+                            Event::FollowerRemoved {
+                                follower_id: *follower_id,
+                            },
+                            cx,
+                        );
+                    }
+                }
+
+                if let Some(roster_before_disconnect) = this.roster_before_disconnect.take() {
+                    this.recently_departed.extend(
+                        roster_before_disconnect
+                            .into_iter()
+                            .filter(|user_id| !this.participant_user_ids.contains(user_id)),
+                    );
+                }
+
                 this.pending_room_update.take();
                 if this.should_leave() {
                     log::info!("room is empty, leaving");
@@ -954,6 +3306,9 @@ impl Room {
                     user_store.set_participant_indices(participant_indices_by_user_id, cx);
                 });
 
+                this.update_project_occupancy(cx);
+                this.check_capacity_crossing(cx);
+
                 this.check_invariants();
                 this.room_update_completed_tx.try_send(Some(())).ok();
                 cx.notify();
@@ -990,9 +3345,9 @@ impl Room {
                     .get_mut(&user_id)
                     .ok_or_else(|| anyhow!("subscribed to track by unknown participant"))?;
                 participant.video_tracks.insert(track_id.clone(), track);
-                cx.emit(Event::RemoteVideoTracksChanged {
-                    participant_id: participant.peer_id,
-                });
+                participant.video_enabled = true;
+                let participant_id = participant.peer_id;
+                self.emit_event(Event::RemoteVideoTracksChanged { participant_id }, cx);
             }
 
             RoomUpdate::UnsubscribedFromRemoteVideoTrack {
@@ -1005,9 +3360,9 @@ impl Room {
                     .get_mut(&user_id)
                     .ok_or_else(|| anyhow!("unsubscribed from track by unknown participant"))?;
                 participant.video_tracks.remove(&track_id);
-                cx.emit(Event::RemoteVideoTracksChanged {
-                    participant_id: participant.peer_id,
-                });
+                participant.video_enabled = !participant.video_tracks.is_empty();
+                let participant_id = participant.peer_id;
+                self.emit_event(Event::RemoteVideoTracksChanged { participant_id }, cx);
             }
 
             RoomUpdate::ActiveSpeakersChanged { speakers } => {
@@ -1016,8 +3371,13 @@ impl Room {
                     .filter_map(|speaker_sid| speaker_sid.parse().ok())
                     .collect::<Vec<u64>>();
                 speaker_ids.sort_unstable();
+                self.speech_sequence += 1;
+                let speech_sequence = self.speech_sequence;
                 for (sid, participant) in &mut self.remote_participants {
                     participant.speaking = speaker_ids.binary_search(sid).is_ok();
+                    if participant.speaking {
+                        participant.last_spoke_sequence = Some(speech_sequence);
+                    }
                 }
                 if let Some(id) = self.client.user_id() {
                     if let Some(room) = &mut self.live_kit {
@@ -1027,8 +3387,9 @@ impl Room {
             }
 
             RoomUpdate::RemoteAudioTrackMuteChanged { track_id, muted } => {
-                let mut found = false;
+                let mut changed_participant_id = None;
                 for participant in &mut self.remote_participants.values_mut() {
+                    let mut found = false;
                     for track in participant.audio_tracks.values() {
                         if track.sid() == track_id {
                             found = true;
@@ -1036,10 +3397,16 @@ impl Room {
                         }
                     }
                     if found {
-                        participant.muted = muted;
+                        if participant.muted != muted {
+                            participant.muted = muted;
+                            changed_participant_id = Some(participant.peer_id);
+                        }
                         break;
                     }
                 }
+                if let Some(participant_id) = changed_participant_id {
+                    self.emit_event(Event::RemoteAudioTracksChanged { participant_id }, cx);
+                }
             }
 
             RoomUpdate::SubscribedToRemoteAudioTrack(track, publication) => {
@@ -1060,10 +3427,9 @@ impl Room {
                     .ok_or_else(|| anyhow!("subscribed to track by unknown participant"))?;
                 participant.audio_tracks.insert(track_id.clone(), track);
                 participant.muted = publication.is_muted();
+                let participant_id = participant.peer_id;
 
-                cx.emit(Event::RemoteAudioTracksChanged {
-                    participant_id: participant.peer_id,
-                });
+                self.emit_event(Event::RemoteAudioTracksChanged { participant_id }, cx);
             }
 
             RoomUpdate::UnsubscribedFromRemoteAudioTrack {
@@ -1076,9 +3442,8 @@ impl Room {
                     .get_mut(&user_id)
                     .ok_or_else(|| anyhow!("unsubscribed from track by unknown participant"))?;
                 participant.audio_tracks.remove(&track_id);
-                cx.emit(Event::RemoteAudioTracksChanged {
-                    participant_id: participant.peer_id,
-                });
+                let participant_id = participant.peer_id;
+                self.emit_event(Event::RemoteAudioTracksChanged { participant_id }, cx);
             }
 
             RoomUpdate::LocalAudioTrackUnpublished { publication } => {
@@ -1134,31 +3499,85 @@ impl Room {
         initial_project_id: Option<u64>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
-        if self.status.is_offline() {
-            return Task::ready(Err(anyhow!("room is offline")));
+        self.call_with_context(called_user_id, initial_project_id, None, cx)
+    }
+
+    pub(crate) fn call_with_context(
+        &mut self,
+        called_user_id: u64,
+        initial_project_id: Option<u64>,
+        context: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if let Err(error) = self.ensure_connected() {
+            return Task::ready(Err(error));
+        }
+
+        // If we're already calling this user, piggyback on the in-flight request instead of
+        // sending a second `proto::Call` for it.
+        if let Some(call) = self.in_flight_calls.get(&called_user_id).cloned() {
+            return cx.spawn(|_, _| async move { call.await.map_err(|error| anyhow!(error)) });
         }
 
         cx.notify();
         let client = self.client.clone();
         let room_id = self.id;
         self.pending_call_count += 1;
-        cx.spawn(move |this, mut cx| async move {
-            let result = client
-                .request(proto::Call {
-                    room_id,
-                    called_user_id,
-                    initial_project_id,
+        self.outgoing_calls.push(called_user_id);
+        let call = cx
+            .spawn(move |this, mut cx| async move {
+                let result = client
+                    .request(proto::Call {
+                        room_id,
+                        called_user_id,
+                        initial_project_id,
+                        context,
+                    })
+                    .await;
+                this.update(&mut cx, |this, cx| {
+                    this.pending_call_count -= 1;
+                    this.outgoing_calls.retain(|user_id| *user_id != called_user_id);
+                    this.in_flight_calls.remove(&called_user_id);
+                    if this.should_leave() {
+                        this.leave(cx).detach_and_log_err(cx);
+                    }
+                    cx.notify();
                 })
-                .await;
-            this.update(&mut cx, |this, cx| {
-                this.pending_call_count -= 1;
-                if this.should_leave() {
-                    this.leave(cx).detach_and_log_err(cx);
-                }
-            })?;
-            result?;
-            Ok(())
-        })
+                .map_err(|error| error.to_string())?;
+                result.map(|_| ()).map_err(|error| error.to_string())
+            })
+            .shared();
+        self.in_flight_calls.insert(called_user_id, call.clone());
+        cx.spawn(|_, _| async move { call.await.map_err(|error| anyhow!(error)) })
+    }
+
+    /// Ids of the users we've called who haven't yet accepted, declined, or been canceled.
+    /// Used by the UI to show a "calling Alice…" state.
+    pub fn outgoing_calls(&self) -> &[u64] {
+        &self.outgoing_calls
+    }
+
+    /// Like [`Room::call`], but if the room is currently full (see [`Room::max_participants`]),
+    /// queues the invite instead of failing outright. Queued invites are dispatched, in order,
+    /// as soon as a slot opens up - see [`Room::check_capacity_crossing`].
+    pub fn queue_call(
+        &mut self,
+        called_user_id: u64,
+        initial_project_id: Option<u64>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if self.at_capacity {
+            self.queued_calls.push(called_user_id);
+            cx.notify();
+            Task::ready(Ok(()))
+        } else {
+            self.call(called_user_id, initial_project_id, cx)
+        }
+    }
+
+    /// Ids of users queued via [`Room::queue_call`], in dispatch order.
+    pub fn queued_calls(&self) -> &[u64] {
+        &self.queued_calls
     }
 
     pub fn join_project(
@@ -1170,7 +3589,7 @@ impl Room {
     ) -> Task<Result<Model<Project>>> {
         let client = self.client.clone();
         let user_store = self.user_store.clone();
-        cx.emit(Event::RemoteProjectJoined { project_id: id });
+        self.emit_event(Event::RemoteProjectJoined { project_id: id }, cx);
         cx.spawn(move |this, mut cx| async move {
             let project =
                 Project::in_room(id, client, user_store, language_registry, fs, cx.clone()).await?;
@@ -1212,21 +3631,86 @@ impl Room {
             })??;
 
             // If the user's location is in this project, it changes from UnsharedProject to SharedProject.
-            this.update(&mut cx, |this, cx| {
+            let set_location = this.update(&mut cx, |this, cx| {
                 this.shared_projects.insert(project.downgrade());
+                this.shared_projects_order.push(project.downgrade());
                 let active_project = this.local_participant.active_project.as_ref();
                 if active_project.map_or(false, |location| *location == project) {
-                    this.set_location(Some(&project), cx)
+                    Some(this.set_location(Some(&project), cx))
                 } else {
-                    Task::ready(Ok(()))
+                    None
                 }
-            })?
-            .await?;
+            })?;
+            if let Some(set_location) = set_location {
+                set_location.await?;
+            }
 
             Ok(response.project_id)
         })
     }
 
+    /// Shares several projects concurrently rather than one at a time, for callers (e.g.
+    /// restoring a previous session's windows at startup) that would otherwise issue the
+    /// `ShareProject` round trip N times in sequence. Resolves with the shared ids in the same
+    /// order as `projects`, or the first error if any project failed to share - in which case
+    /// some of the others may still have been shared successfully.
+    pub fn publish_projects(
+        &mut self,
+        projects: Vec<Model<Project>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<u64>>> {
+        let tasks = projects
+            .into_iter()
+            .map(|project| self.share_project(project, cx))
+            .collect::<Vec<_>>();
+        cx.background_executor().spawn(async move {
+            futures::future::try_join_all(tasks).await
+        })
+    }
+
+    /// Flips a project we're hosting between read-only and read-write for everyone else in the
+    /// room. Rejected by the server (and not even attempted here) if we're not that project's
+    /// host - see [`Room::project_access`].
+    pub fn set_project_access(
+        &mut self,
+        project_id: u64,
+        access: ProjectAccess,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if let Err(error) = self.ensure_connected() {
+            return Task::ready(Err(error));
+        } else if !self
+            .local_participant
+            .projects
+            .iter()
+            .any(|project| project.id == project_id)
+        {
+            return Task::ready(Err(anyhow!(
+                "cannot change access to a project we're not hosting"
+            )));
+        }
+
+        let read_only = access == ProjectAccess::ReadOnly;
+        if let Some(project) = self
+            .local_participant
+            .projects
+            .iter_mut()
+            .find(|project| project.id == project_id)
+        {
+            project.read_only = read_only;
+        }
+        cx.notify();
+
+        let request = self.client.request(proto::SetProjectAccess {
+            project_id,
+            read_only,
+        });
+        cx.background_executor().spawn(async move {
+            request.await?;
+            Ok(())
+        })
+    }
+
     pub(crate) fn unshare_project(
         &mut self,
         project: Model<Project>,
@@ -1240,53 +3724,258 @@ impl Room {
         self.client.send(proto::UnshareProject { project_id })?;
         project.update(cx, |this, cx| this.unshare(cx))?;
 
+        self.shared_projects.remove(&project.downgrade());
+        self.shared_projects_order
+            .retain(|shared_project| *shared_project != project);
+
         if self.local_participant.active_project == Some(project.downgrade()) {
             self.set_location(Some(&project), cx).detach_and_log_err(cx);
         }
         Ok(())
     }
 
-    pub(crate) fn set_location(
+    /// Like [`Room::set_location`], but publishes `project` first via [`Room::share_project`] if
+    /// it isn't shared yet, so the common "start sharing what I'm looking at" flow doesn't need
+    /// two separate round trips from the caller.
+    pub fn set_location_auto_publish(
+        &mut self,
+        project: &Model<Project>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ParticipantLocation>> {
+        let project = project.clone();
+        let share = self.share_project(project.clone(), cx);
+        cx.spawn(|this, mut cx| async move {
+            share.await?;
+            this.update(&mut cx, |this, cx| this.set_location(Some(&project), cx))?
+                .await
+        })
+    }
+
+    /// Broadcasts the local participant's location to the server, resolving to the location
+    /// that was actually broadcast (e.g. a project id gets translated to `SharedProject`).
+    pub fn set_location(
         &mut self,
         project: Option<&Model<Project>>,
         cx: &mut ModelContext<Self>,
-    ) -> Task<Result<()>> {
-        if self.status.is_offline() {
-            return Task::ready(Err(anyhow!("room is offline")));
+    ) -> Task<Result<ParticipantLocation>> {
+        if let Err(error) = self.ensure_connected() {
+            return Task::ready(Err(error));
         }
 
         let client = self.client.clone();
         let room_id = self.id;
-        let location = if let Some(project) = project {
+        let (location, broadcast_location) = if let Some(project) = project {
             self.local_participant.active_project = Some(project.downgrade());
             if let Some(project_id) = project.read(cx).remote_id() {
-                proto::participant_location::Variant::SharedProject(
-                    proto::participant_location::SharedProject { id: project_id },
+                (
+                    proto::participant_location::Variant::SharedProject(
+                        proto::participant_location::SharedProject {
+                            id: project_id,
+                            open_path: self.local_open_path.as_ref().map(ProjectPath::to_proto),
+                            anchor: self.local_open_anchor.map(|anchor| anchor.to_proto()),
+                        },
+                    ),
+                    ParticipantLocation::SharedProject { project_id },
                 )
             } else {
-                proto::participant_location::Variant::UnsharedProject(
-                    proto::participant_location::UnsharedProject {},
+                (
+                    proto::participant_location::Variant::UnsharedProject(
+                        proto::participant_location::UnsharedProject {},
+                    ),
+                    ParticipantLocation::UnsharedProject,
                 )
             }
         } else {
             self.local_participant.active_project = None;
-            proto::participant_location::Variant::External(proto::participant_location::External {})
+            (
+                proto::participant_location::Variant::External(
+                    proto::participant_location::External {},
+                ),
+                ParticipantLocation::External,
+            )
+        };
+
+        // Skip the round trip if this is exactly what we last broadcast - e.g. re-entering a
+        // project we're already sharing our location for. Keyed on `open_path`/`open_anchor` too,
+        // since `set_open_path` re-broadcasts the same `ParticipantLocation` with a different
+        // open file or cursor position.
+        let broadcast_key = (
+            broadcast_location,
+            self.local_open_path.clone(),
+            self.local_open_anchor,
+        );
+        if self.last_broadcast_location.as_ref() == Some(&broadcast_key) {
+            return Task::ready(Ok(broadcast_location));
+        }
+        self.last_broadcast_location = Some(broadcast_key);
+
+        cx.notify();
+        let broadcast = cx
+            .spawn(move |this, mut cx| async move {
+                // The project may have been unshared while this broadcast was in flight (e.g.
+                // the user switched away from it quickly). Re-validate against the freshest
+                // known state right before sending, rather than the state at the time
+                // `set_location` was called, so we don't broadcast a location that references a
+                // project nobody can join anymore.
+                let (location, broadcast_location) =
+                    if let proto::participant_location::Variant::SharedProject(shared_project) =
+                        &location
+                    {
+                        let still_shared = this
+                            .update(&mut cx, |this, _| {
+                                this.local_participant
+                                    .projects
+                                    .iter()
+                                    .any(|project| project.id == shared_project.id)
+                            })
+                            .map_err(|error| error.to_string())?;
+                        if still_shared {
+                            (location, broadcast_location)
+                        } else {
+                            (
+                                proto::participant_location::Variant::External(
+                                    proto::participant_location::External {},
+                                ),
+                                ParticipantLocation::External,
+                            )
+                        }
+                    } else {
+                        (location, broadcast_location)
+                    };
+
+                #[cfg(any(test, feature = "test-support"))]
+                this.update(&mut cx, |this, _| this.location_broadcasts_sent += 1)
+                    .map_err(|error| error.to_string())?;
+
+                client
+                    .request(proto::UpdateParticipantLocation {
+                        room_id,
+                        location: Some(proto::ParticipantLocation {
+                            variant: Some(location),
+                        }),
+                    })
+                    .await
+                    .map_err(|error| error.to_string())?;
+                Ok(broadcast_location)
+            })
+            .shared();
+        self.pending_location_broadcast = Some(broadcast.clone());
+
+        cx.background_executor()
+            .spawn(async move { broadcast.await.map_err(|error| anyhow!(error)) })
+    }
+
+    /// Whether the app is currently in the foreground. See [`Room::set_foreground`].
+    pub fn is_foreground(&self) -> bool {
+        self.foreground
+    }
+
+    /// Call when the app is minimized/backgrounded or restored, so we don't keep broadcasting an
+    /// active project nobody's actually looking at. Backgrounding broadcasts
+    /// [`ParticipantLocation::External`] in place of whatever project was active; foregrounding
+    /// restores it, if that project is still open. A no-op if the foreground state isn't changing.
+    pub fn set_foreground(&mut self, foreground: bool, cx: &mut ModelContext<Self>) {
+        if self.foreground == foreground {
+            return;
+        }
+        self.foreground = foreground;
+
+        if foreground {
+            if let Some(project) = self
+                .backgrounded_active_project
+                .take()
+                .and_then(|project| project.upgrade())
+            {
+                self.set_location(Some(&project), cx).detach_and_log_err(cx);
+            }
+        } else if let Some(project) = self
+            .local_participant
+            .active_project
+            .clone()
+            .and_then(|project| project.upgrade())
+        {
+            self.backgrounded_active_project = Some(project.downgrade());
+            self.set_location(None, cx).detach_and_log_err(cx);
+        }
+    }
+
+    /// Updates the buffer/file (and, optionally, the cursor/selection within it) the local user
+    /// has open within their active shared project, and re-broadcasts the location so other
+    /// participants' [`RemoteParticipant::open_path`]/[`RemoteParticipant::open_anchor`] pick it
+    /// up. The path and anchor are always stored; without an active project nothing is broadcast.
+    pub fn set_open_path(
+        &mut self,
+        open_path: Option<ProjectPath>,
+        anchor: Option<ViewAnchor>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ParticipantLocation>> {
+        self.local_open_path = open_path;
+        self.local_open_anchor = anchor;
+        let Some(project) = self
+            .local_participant
+            .active_project
+            .as_ref()
+            .and_then(|project| project.upgrade())
+        else {
+            return Task::ready(Ok(ParticipantLocation::External));
         };
+        self.set_location(Some(&project), cx)
+    }
+
+    /// Joins or leaves a listen-only observer mode: mic/camera/screen publishing is disabled and
+    /// [`RemoteParticipant::is_observer`] is surfaced to everyone else in the room. Entering
+    /// observer mode stops any in-progress screen share and mutes the microphone; it doesn't
+    /// restore either when leaving observer mode again, matching how [`Room::toggle_mute`] and
+    /// [`Room::share_screen`] already require an explicit call to resume.
+    pub fn set_observer_mode(
+        &mut self,
+        is_observer: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if let Err(error) = self.ensure_connected() {
+            return Task::ready(Err(error));
+        }
 
+        self.local_participant.is_observer = is_observer;
+        if is_observer {
+            if self.is_screen_sharing() {
+                self.unshare_screen(cx).log_err();
+            }
+            if let Some(live_kit) = self.live_kit.as_mut() {
+                live_kit.muted_by_user = true;
+            }
+            self.set_mute(true, cx);
+        }
         cx.notify();
+
+        let client = self.client.clone();
+        let room_id = self.id;
         cx.background_executor().spawn(async move {
             client
-                .request(proto::UpdateParticipantLocation {
+                .request(proto::SetParticipantObserverMode {
                     room_id,
-                    location: Some(proto::ParticipantLocation {
-                        variant: Some(location),
-                    }),
+                    is_observer,
                 })
                 .await?;
             Ok(())
         })
     }
 
+    /// Re-broadcasts the local participant's current location right away, e.g. when the app
+    /// regains focus after being backgrounded. Goes through [`Room::set_location`] with
+    /// whatever `active_project` already holds, rather than waiting for something else to call
+    /// `set_location` again.
+    pub fn flush_location(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let project = self
+            .local_participant
+            .active_project
+            .as_ref()
+            .and_then(|project| project.upgrade());
+        let set_location = self.set_location(project.as_ref(), cx);
+        cx.background_executor()
+            .spawn(async move { set_location.await.map(|_| ()) })
+    }
+
     pub fn is_screen_sharing(&self) -> bool {
         self.live_kit.as_ref().map_or(false, |live_kit| {
             !matches!(live_kit.screen_track, LocalTrack::None)
@@ -1307,6 +3996,71 @@ impl Room {
         })
     }
 
+    /// Whether the host (or a co-host) has forced the local microphone off. Distinct from
+    /// [`Room::is_muted`], which also covers the user muting themselves - [`Room::unmute`]
+    /// refuses to run while this is true.
+    pub fn is_force_muted(&self) -> bool {
+        self.force_muted
+    }
+
+    /// Unmutes the local microphone, unless the host has force-muted this user (see
+    /// [`Room::is_force_muted`]), in which case it's left alone until the host lifts it.
+    pub fn unmute(&mut self, cx: &mut ModelContext<Self>) -> Result<()> {
+        if self.force_muted {
+            return Err(anyhow!(
+                "you were muted by the host and can't unmute yourself"
+            ));
+        }
+        if let Some(live_kit) = self.live_kit.as_mut() {
+            live_kit.muted_by_user = false;
+            live_kit.deafened = false;
+        }
+        self.set_mute(false, cx);
+        Ok(())
+    }
+
+    /// Whether push-to-talk mode is enabled. See [`Room::set_push_to_talk`].
+    pub fn is_push_to_talk(&self) -> bool {
+        self.push_to_talk
+    }
+
+    /// Turns push-to-talk mode on or off. Enabling it immediately mutes the mic - from then on,
+    /// only [`Room::push_to_talk_begin`]/[`Room::push_to_talk_end`] unmute it, bracketing a held
+    /// key. Disabling it leaves the mic muted or not, whichever [`Room::push_to_talk_end`] last
+    /// left it as.
+    pub fn set_push_to_talk(&mut self, enabled: bool, cx: &mut ModelContext<Self>) {
+        self.push_to_talk = enabled;
+        if enabled {
+            if let Some(live_kit) = self.live_kit.as_mut() {
+                live_kit.muted_by_user = true;
+            }
+            self.set_mute(true, cx);
+        }
+    }
+
+    /// Unmutes the mic while a push-to-talk key is held down. A no-op outside push-to-talk mode,
+    /// or while the host has force-muted this user - see [`Room::is_force_muted`].
+    pub fn push_to_talk_begin(&mut self, cx: &mut ModelContext<Self>) {
+        if !self.push_to_talk || self.force_muted {
+            return;
+        }
+        if let Some(live_kit) = self.live_kit.as_mut() {
+            live_kit.muted_by_user = false;
+        }
+        self.set_mute(false, cx);
+    }
+
+    /// Re-mutes the mic once a push-to-talk key is released. A no-op outside push-to-talk mode.
+    pub fn push_to_talk_end(&mut self, cx: &mut ModelContext<Self>) {
+        if !self.push_to_talk {
+            return;
+        }
+        if let Some(live_kit) = self.live_kit.as_mut() {
+            live_kit.muted_by_user = true;
+        }
+        self.set_mute(true, cx);
+    }
+
     pub fn is_speaking(&self) -> bool {
         self.live_kit
             .as_ref()
@@ -1320,7 +4074,7 @@ impl Room {
     pub fn can_use_microphone(&self) -> bool {
         use proto::ChannelRole::*;
         match self.local_participant.role {
-            Admin | Member | Talker => true,
+            Admin | CoHost | Member | Talker => true,
             Guest | Banned => false,
         }
     }
@@ -1328,15 +4082,27 @@ impl Room {
     pub fn can_share_projects(&self) -> bool {
         use proto::ChannelRole::*;
         match self.local_participant.role {
-            Admin | Member => true,
+            Admin | CoHost | Member => true,
             Guest | Banned | Talker => false,
         }
     }
 
+    /// True if the local participant can moderate the room: kick participants or force-mute
+    /// their microphones. Unlike [`Room::local_participant_is_admin`], this also admits
+    /// co-hosts, who share moderation power but not the ability to promote/demote others.
+    pub fn can_moderate(&self) -> bool {
+        use proto::ChannelRole::*;
+        matches!(self.local_participant.role, Admin | CoHost)
+    }
+
     #[track_caller]
     pub fn share_microphone(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
-        if self.status.is_offline() {
-            return Task::ready(Err(anyhow!("room is offline")));
+        if let Err(error) = self.ensure_connected() {
+            return Task::ready(Err(error));
+        } else if self.local_participant.is_observer {
+            return Task::ready(Err(anyhow!(
+                "can't share your microphone while in observer mode"
+            )));
         }
 
         let publish_id = if let Some(live_kit) = self.live_kit.as_mut() {
@@ -1411,8 +4177,12 @@ impl Room {
     }
 
     pub fn share_screen(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
-        if self.status.is_offline() {
-            return Task::ready(Err(anyhow!("room is offline")));
+        if let Err(error) = self.ensure_connected() {
+            return Task::ready(Err(error));
+        } else if self.local_participant.is_observer {
+            return Task::ready(Err(anyhow!(
+                "can't share your screen while in observer mode"
+            )));
         } else if self.is_screen_sharing() {
             return Task::ready(Err(anyhow!("screen was already shared")));
         }
@@ -1491,7 +4261,219 @@ impl Room {
         })
     }
 
+    pub fn metrics(&self) -> RoomMetrics {
+        self.metrics
+    }
+
+    pub fn max_participants(&self) -> Option<usize> {
+        self.max_participants
+    }
+
+    /// Sets a soft participant cap used only to drive [`Event::RoomFull`] /
+    /// [`Event::RoomHasCapacity`], e.g. so the UI can gray out an invite button. This is a
+    /// local-only preference; the server does not enforce any room capacity.
+    pub fn set_max_participants(&mut self, max: Option<usize>, cx: &mut ModelContext<Self>) {
+        self.max_participants = max;
+        self.check_capacity_crossing(cx);
+    }
+
+    /// Overrides [`DEFAULT_MASS_REMOVAL_RESYNC_THRESHOLD`] for this room - the fraction of the
+    /// roster (0.0 to 1.0) that can disappear in one [`Room::apply_room_update`] diff before it's
+    /// treated as suspicious and triggers a [`Room::resync`] instead of being applied.
+    pub fn set_mass_removal_resync_threshold(&mut self, threshold: f64) {
+        self.mass_removal_resync_threshold = threshold;
+    }
+
+    /// Total number of participants, including ourselves, counted against `max_participants`.
+    fn participant_count(&self) -> usize {
+        self.remote_participants.len() + 1
+    }
+
+    /// Recomputes [`Self::project_occupancy`] from current participant locations, emitting
+    /// [`Event::ProjectOccupancyChanged`] for every project whose count changed - including
+    /// dropping to `0` for a project that no longer has anyone in it.
+    fn update_project_occupancy(&mut self, cx: &mut ModelContext<Self>) {
+        let mut occupancy_after = HashMap::default();
+        for participant in self.remote_participants.values() {
+            if let ParticipantLocation::SharedProject { project_id } = participant.location {
+                *occupancy_after.entry(project_id).or_insert(0_usize) += 1;
+            }
+        }
+
+        let mut project_ids: HashSet<u64> = self.project_occupancy.keys().copied().collect();
+        project_ids.extend(occupancy_after.keys().copied());
+
+        for project_id in project_ids {
+            let count = occupancy_after.get(&project_id).copied().unwrap_or(0);
+            if self.project_occupancy.get(&project_id).copied().unwrap_or(0) != count {
+                self.emit_event(Event::ProjectOccupancyChanged { project_id, count }, cx);
+            }
+        }
+
+        self.project_occupancy = occupancy_after;
+    }
+
+    fn check_capacity_crossing(&mut self, cx: &mut ModelContext<Self>) {
+        self.peak_participant_count = self.peak_participant_count.max(self.participant_count());
+        let is_full = self
+            .max_participants
+            .map_or(false, |max| self.participant_count() >= max);
+        if is_full && !self.at_capacity {
+            self.at_capacity = true;
+            self.emit_event(Event::RoomFull, cx);
+        } else if !is_full && self.at_capacity {
+            self.at_capacity = false;
+            self.emit_event(Event::RoomHasCapacity, cx);
+            while !self.queued_calls.is_empty()
+                && self
+                    .max_participants
+                    .map_or(true, |max| self.participant_count() < max)
+            {
+                let called_user_id = self.queued_calls.remove(0);
+                self.call(called_user_id, None, cx).detach_and_log_err(cx);
+            }
+        }
+    }
+
+    /// The SFU auth token from the `CreateRoom`/`JoinRoom` response used to connect to LiveKit,
+    /// if this deployment has real-time audio/video configured. `None` for text-only rooms.
+    pub fn media_token(&self) -> Option<&str> {
+        self.media_token.as_deref()
+    }
+
+    /// Replaces the current media token and notifies listeners via
+    /// [`Event::MediaTokenRefreshed`]. Nothing in this codebase calls this yet: neither
+    /// `RejoinRoomResponse` nor any other server message currently carries a refreshed
+    /// `LiveKitConnectionInfo`, so there is no live path that nears expiry and refreshes. This
+    /// exists as the seam a future refresh RPC would call into.
+    fn set_media_token(&mut self, token: String, cx: &mut ModelContext<Self>) {
+        self.media_token = Some(token);
+        self.emit_event(Event::MediaTokenRefreshed, cx);
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn refresh_media_token_for_test(&mut self, token: String, cx: &mut ModelContext<Self>) {
+        self.set_media_token(token, cx);
+    }
+
+    pub fn noise_suppression_enabled(&self) -> bool {
+        self.local_participant.noise_suppression_enabled
+    }
+
+    /// Toggles local noise suppression. This is a local-only preference (not broadcast to
+    /// other participants) that persists across reconnects since it lives on `LocalParticipant`.
+    pub fn set_noise_suppression(&mut self, enabled: bool, cx: &mut ModelContext<Self>) {
+        self.local_participant.noise_suppression_enabled = enabled;
+        cx.notify();
+    }
+
+    pub fn echo_cancellation_enabled(&self) -> bool {
+        self.local_participant.echo_cancellation_enabled
+    }
+
+    /// Toggles local echo cancellation, alongside [`Room::set_noise_suppression`]. This is a
+    /// local-only preference (not broadcast to other participants) that persists across
+    /// reconnects since it lives on `LocalParticipant`.
+    pub fn set_echo_cancellation(&mut self, enabled: bool, cx: &mut ModelContext<Self>) {
+        self.local_participant.echo_cancellation_enabled = enabled;
+        cx.notify();
+    }
+
+    pub fn is_video_enabled(&self) -> bool {
+        self.local_participant.video_enabled
+    }
+
+    /// Toggles the local camera preference, restored automatically across reconnects since the
+    /// flag lives on `LocalParticipant`. This crate does not yet talk to a real camera capture
+    /// backend, so no track is actually started or stopped — this is preference state only,
+    /// surfaced to the UI and to other participants once a capture backend exists.
+    pub fn set_video_enabled(&mut self, enabled: bool, cx: &mut ModelContext<Self>) {
+        if self.local_participant.video_enabled != enabled {
+            self.local_participant.video_enabled = enabled;
+            self.emit_event(Event::VideoChanged, cx);
+            cx.notify();
+        }
+    }
+
+    pub fn enable_video(&mut self, cx: &mut ModelContext<Self>) {
+        self.set_video_enabled(true, cx);
+    }
+
+    pub fn disable_video(&mut self, cx: &mut ModelContext<Self>) {
+        self.set_video_enabled(false, cx);
+    }
+
+    pub fn audio_input_device_id(&self) -> Option<&str> {
+        self.local_participant.audio_input_device_id.as_deref()
+    }
+
+    pub fn audio_output_device_id(&self) -> Option<&str> {
+        self.local_participant.audio_output_device_id.as_deref()
+    }
+
+    /// Selects the microphone used for capture, restored automatically across reconnects since
+    /// the selection lives on `LocalParticipant`. This crate does not yet talk to a real device
+    /// enumeration backend, so the only validation performed is rejecting an empty device id.
+    pub fn set_audio_input(&mut self, device_id: String, cx: &mut ModelContext<Self>) {
+        if device_id.is_empty() {
+            self.emit_event(
+                Event::Error {
+                    message: "cannot select an empty audio input device".into(),
+                },
+                cx,
+            );
+            return;
+        }
+        self.local_participant.audio_input_device_id = Some(device_id);
+        cx.notify();
+    }
+
+    /// Selects the speaker used for playback. See [`Room::set_audio_input`] for the caveat
+    /// about device validation.
+    pub fn set_audio_output(&mut self, device_id: String, cx: &mut ModelContext<Self>) {
+        if device_id.is_empty() {
+            self.emit_event(
+                Event::Error {
+                    message: "cannot select an empty audio output device".into(),
+                },
+                cx,
+            );
+            return;
+        }
+        self.local_participant.audio_output_device_id = Some(device_id);
+        cx.notify();
+    }
+
+    pub fn output_gain(&self) -> f32 {
+        self.local_participant.output_gain
+    }
+
+    pub fn input_gain(&self) -> f32 {
+        self.local_participant.input_gain
+    }
+
+    /// Sets the master output volume, clamped to [`GAIN_RANGE`] and restored automatically
+    /// across reconnects since it lives on `LocalParticipant`. Separate from any per-peer volume
+    /// a caller might apply on top. This crate does not yet talk to a real gain-control API on
+    /// the LiveKit room backend, so no gain is actually applied to played-back audio yet - this
+    /// is preference state only, surfaced to the UI until that hook exists.
+    pub fn set_output_gain(&mut self, gain: f32, cx: &mut ModelContext<Self>) {
+        self.local_participant.output_gain = gain.clamp(*GAIN_RANGE.start(), *GAIN_RANGE.end());
+        cx.notify();
+    }
+
+    /// Sets microphone sensitivity, alongside [`Room::set_output_gain`]. See its doc comment for
+    /// the clamping and persistence behavior, and the caveat about there being no real gain-
+    /// control hook to apply this to yet.
+    pub fn set_input_gain(&mut self, gain: f32, cx: &mut ModelContext<Self>) {
+        self.local_participant.input_gain = gain.clamp(*GAIN_RANGE.start(), *GAIN_RANGE.end());
+        cx.notify();
+    }
+
     pub fn toggle_mute(&mut self, cx: &mut ModelContext<Self>) {
+        if self.local_participant.is_observer || self.force_muted {
+            return;
+        }
         if let Some(live_kit) = self.live_kit.as_mut() {
             // When unmuting, undeafen if the user was deafened before.
             let was_deafened = live_kit.deafened;
@@ -1507,9 +4489,7 @@ impl Room {
             let muted = live_kit.muted_by_user;
             let should_undeafen = was_deafened && !live_kit.deafened;
 
-            if let Some(task) = self.set_mute(muted, cx) {
-                task.detach_and_log_err(cx);
-            }
+            self.set_mute(muted, cx);
 
             if should_undeafen {
                 if let Some(task) = self.set_deafened(false, cx) {
@@ -1532,17 +4512,13 @@ impl Room {
             }
 
             if should_change_mute {
-                if let Some(task) = self.set_mute(deafened, cx) {
-                    task.detach_and_log_err(cx);
-                }
+                self.set_mute(deafened, cx);
             }
         }
     }
 
     pub fn unshare_screen(&mut self, cx: &mut ModelContext<Self>) -> Result<()> {
-        if self.status.is_offline() {
-            return Err(anyhow!("room is offline"));
-        }
+        self.ensure_connected()?;
 
         let live_kit = self
             .live_kit
@@ -1600,12 +4576,16 @@ impl Room {
         }))
     }
 
-    fn set_mute(
-        &mut self,
-        should_mute: bool,
-        cx: &mut ModelContext<Room>,
-    ) -> Option<Task<Result<()>>> {
-        let live_kit = self.live_kit.as_mut()?;
+    /// Local intent (`live_kit.muted_by_user`, set by the caller before this runs) is always the
+    /// source of truth for `Room::is_muted()`; this just publishes that intent to the SFU.
+    /// Storing the resulting task in `self.pending_mute_update` (rather than handing it back for
+    /// the caller to detach) means a new call here drops - and so cancels - whatever publish was
+    /// previously in flight, so a stale ack from a superseded toggle can never land after a more
+    /// recent one and leave the remote-visible state behind local intent.
+    fn set_mute(&mut self, should_mute: bool, cx: &mut ModelContext<Room>) {
+        let Some(_) = self.live_kit.as_ref() else {
+            return;
+        };
         cx.notify();
 
         if should_mute {
@@ -1613,8 +4593,12 @@ impl Room {
         } else {
             Audio::play_sound(Sound::Unmute, cx);
         }
+        self.emit_event(Event::LocalMuteChanged { muted: should_mute }, cx);
 
-        match &mut live_kit.microphone_track {
+        let Some(live_kit) = self.live_kit.as_mut() else {
+            return;
+        };
+        let task = match &mut live_kit.microphone_track {
             LocalTrack::None => {
                 if should_mute {
                     None
@@ -1627,9 +4611,94 @@ impl Room {
                 cx.foreground_executor()
                     .spawn(track_publication.set_mute(should_mute)),
             ),
+        };
+
+        self.pending_mute_update = task.map(|task| {
+            cx.spawn(|_, _| async move {
+                task.await.log_err();
+            })
+        });
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn call_for_test(
+        &mut self,
+        called_user_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.call(called_user_id, None, cx)
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn set_update_epoch_for_test(&mut self, epoch: u64) {
+        self.update_epoch = epoch;
+    }
+
+    /// Stamps `user_id` as having just spoken, the way a real [`RoomUpdate::ActiveSpeakersChanged`]
+    /// event would, without needing an actual LiveKit connection. See
+    /// [`Room::participants_by_recent_speech`].
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn mark_speaking_for_test(&mut self, user_id: u64) {
+        self.speech_sequence += 1;
+        let speech_sequence = self.speech_sequence;
+        if let Some(participant) = self.remote_participants.get_mut(&user_id) {
+            participant.speaking = true;
+            participant.last_spoke_sequence = Some(speech_sequence);
         }
     }
 
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn set_status_for_test(&mut self, status: RoomStatus, cx: &mut ModelContext<Self>) {
+        self.status = status;
+        cx.notify();
+    }
+
+    /// Removes a project from the server-acknowledged project list without going through the
+    /// real unshare round trip, simulating the window where a project is unpublished while a
+    /// `set_location` broadcast referencing it is still in flight.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn drop_shared_project_for_test(&mut self, project_id: u64) {
+        self.local_participant
+            .projects
+            .retain(|project| project.id != project_id);
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn apply_room_update_for_test(
+        &mut self,
+        room: proto::Room,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        self.apply_room_update(room, cx)
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn apply_pending_participants_update_for_test(
+        &mut self,
+        pending_participants: Vec<proto::PendingParticipant>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.apply_pending_participants_update(pending_participants, cx)
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn simulate_participant_left_for_test(
+        &mut self,
+        peer_id: proto::PeerId,
+        farewell_message: Option<String>,
+        reason: proto::LeaveReason,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.emit_event(
+            Event::ParticipantFarewell {
+                peer_id,
+                message: farewell_message,
+                reason,
+            },
+            cx,
+        );
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn set_display_sources(&self, sources: Vec<live_kit_client::MacOSDisplay>) {
         self.live_kit
@@ -1704,4 +4773,8 @@ impl RoomStatus {
     pub fn is_online(&self) -> bool {
         matches!(self, RoomStatus::Online)
     }
+
+    pub fn is_rejoining(&self) -> bool {
+        matches!(self, RoomStatus::Rejoining)
+    }
 }
@@ -115,10 +115,11 @@ impl ChannelMembership {
         MembershipSortKey {
             role_order: match self.role {
                 proto::ChannelRole::Admin => 0,
-                proto::ChannelRole::Member => 1,
-                proto::ChannelRole::Banned => 2,
-                proto::ChannelRole::Talker => 3,
-                proto::ChannelRole::Guest => 4,
+                proto::ChannelRole::CoHost => 1,
+                proto::ChannelRole::Member => 2,
+                proto::ChannelRole::Banned => 3,
+                proto::ChannelRole::Talker => 4,
+                proto::ChannelRole::Guest => 5,
             },
             kind_order: match self.kind {
                 proto::channel_member::Kind::Member => 0,
@@ -104,6 +104,10 @@ pub enum ChannelRole {
     /// Admin can read/write and change permissions.
     #[sea_orm(string_value = "admin")]
     Admin,
+    /// CoHost can read/write and moderate a room's participants (remove, mute), but cannot
+    /// change anyone else's role.
+    #[sea_orm(string_value = "co_host")]
+    CoHost,
     /// Member can read/write, but not change permissions.
     #[sea_orm(string_value = "member")]
     #[default]
@@ -126,7 +130,8 @@ impl ChannelRole {
     pub fn should_override(&self, other: Self) -> bool {
         use ChannelRole::*;
         match self {
-            Admin => matches!(other, Member | Banned | Talker | Guest),
+            Admin => matches!(other, CoHost | Member | Banned | Talker | Guest),
+            CoHost => matches!(other, Member | Banned | Talker | Guest),
             Member => matches!(other, Banned | Talker | Guest),
             Talker => matches!(other, Guest),
             Banned => matches!(other, Guest),
@@ -146,7 +151,7 @@ impl ChannelRole {
     pub fn can_see_channel(&self, visibility: ChannelVisibility) -> bool {
         use ChannelRole::*;
         match self {
-            Admin | Member => true,
+            Admin | CoHost | Member => true,
             Guest | Talker => visibility == ChannelVisibility::Public,
             Banned => false,
         }
@@ -156,7 +161,7 @@ impl ChannelRole {
     pub fn can_see_all_descendants(&self) -> bool {
         use ChannelRole::*;
         match self {
-            Admin | Member => true,
+            Admin | CoHost | Member => true,
             Guest | Talker | Banned => false,
         }
     }
@@ -166,7 +171,7 @@ impl ChannelRole {
         use ChannelRole::*;
         match self {
             Guest | Talker => true,
-            Admin | Member | Banned => false,
+            Admin | CoHost | Member | Banned => false,
         }
     }
 
@@ -174,7 +179,7 @@ impl ChannelRole {
     pub fn can_use_microphone(&self) -> bool {
         use ChannelRole::*;
         match self {
-            Admin | Member | Talker => true,
+            Admin | CoHost | Member | Talker => true,
             Guest | Banned => false,
         }
     }
@@ -183,7 +188,7 @@ impl ChannelRole {
     pub fn can_edit_projects(&self) -> bool {
         use ChannelRole::*;
         match self {
-            Admin | Member => true,
+            Admin | CoHost | Member => true,
             Talker | Guest | Banned => false,
         }
     }
@@ -192,7 +197,7 @@ impl ChannelRole {
     pub fn can_read_projects(&self) -> bool {
         use ChannelRole::*;
         match self {
-            Admin | Member | Guest | Talker => true,
+            Admin | CoHost | Member | Guest | Talker => true,
             Banned => false,
         }
     }
@@ -200,16 +205,24 @@ impl ChannelRole {
     pub fn requires_cla(&self) -> bool {
         use ChannelRole::*;
         match self {
-            Admin | Member => true,
+            Admin | CoHost | Member => true,
             Banned | Guest | Talker => false,
         }
     }
+
+    /// True if this role can moderate a room's participants: remove them or force-mute their
+    /// microphone. Unlike `Admin`, a `CoHost` cannot change anyone else's role.
+    pub fn can_moderate_room(&self) -> bool {
+        use ChannelRole::*;
+        matches!(self, Admin | CoHost)
+    }
 }
 
 impl From<proto::ChannelRole> for ChannelRole {
     fn from(value: proto::ChannelRole) -> Self {
         match value {
             proto::ChannelRole::Admin => ChannelRole::Admin,
+            proto::ChannelRole::CoHost => ChannelRole::CoHost,
             proto::ChannelRole::Member => ChannelRole::Member,
             proto::ChannelRole::Talker => ChannelRole::Talker,
             proto::ChannelRole::Guest => ChannelRole::Guest,
@@ -222,6 +235,7 @@ impl From<ChannelRole> for proto::ChannelRole {
     fn from(val: ChannelRole) -> Self {
         match val {
             ChannelRole::Admin => proto::ChannelRole::Admin,
+            ChannelRole::CoHost => proto::ChannelRole::CoHost,
             ChannelRole::Member => proto::ChannelRole::Member,
             ChannelRole::Talker => proto::ChannelRole::Talker,
             ChannelRole::Guest => proto::ChannelRole::Guest,
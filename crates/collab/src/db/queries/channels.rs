@@ -754,7 +754,8 @@ impl Database {
         let role = self.channel_role_for_user(channel, user_id, tx).await?;
         match role {
             Some(ChannelRole::Admin) => Ok(role.unwrap()),
-            Some(ChannelRole::Member)
+            Some(ChannelRole::CoHost)
+            | Some(ChannelRole::Member)
             | Some(ChannelRole::Talker)
             | Some(ChannelRole::Banned)
             | Some(ChannelRole::Guest)
@@ -773,7 +774,9 @@ impl Database {
     ) -> Result<ChannelRole> {
         let channel_role = self.channel_role_for_user(channel, user_id, tx).await?;
         match channel_role {
-            Some(ChannelRole::Admin) | Some(ChannelRole::Member) => Ok(channel_role.unwrap()),
+            Some(ChannelRole::Admin) | Some(ChannelRole::CoHost) | Some(ChannelRole::Member) => {
+                Ok(channel_role.unwrap())
+            }
             Some(ChannelRole::Banned)
             | Some(ChannelRole::Guest)
             | Some(ChannelRole::Talker)
@@ -793,6 +796,7 @@ impl Database {
         let role = self.channel_role_for_user(channel, user_id, tx).await?;
         match role {
             Some(ChannelRole::Admin)
+            | Some(ChannelRole::CoHost)
             | Some(ChannelRole::Member)
             | Some(ChannelRole::Guest)
             | Some(ChannelRole::Talker) => Ok(role.unwrap()),
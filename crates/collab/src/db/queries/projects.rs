@@ -68,6 +68,7 @@ impl Database {
                     connection.owner_id as i32,
                 ))),
                 id: ActiveValue::NotSet,
+                read_only: ActiveValue::NotSet,
             }
             .insert(&*tx)
             .await?;
@@ -141,6 +142,38 @@ impl Database {
         .await
     }
 
+    /// Flips a shared project between read-only and read-write for everyone but its host. Only
+    /// the host's own connection may do this - see `unshare_project` for the same check.
+    pub async fn set_project_access(
+        &self,
+        project_id: ProjectId,
+        connection: ConnectionId,
+        read_only: bool,
+    ) -> Result<TransactionGuard<proto::Room>> {
+        self.project_transaction(project_id, |tx| async move {
+            let project = project::Entity::find_by_id(project_id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("project not found"))?;
+            if project.host_connection()? != connection {
+                return Err(anyhow!("cannot change access to a project hosted by another user"))?;
+            }
+            let room_id = project
+                .room_id
+                .ok_or_else(|| anyhow!("project is not shared in a room"))?;
+
+            project::Entity::update(project::ActiveModel {
+                read_only: ActiveValue::set(read_only),
+                ..project.into_active_model()
+            })
+            .exec(&*tx)
+            .await?;
+
+            self.get_room(room_id, &tx).await
+        })
+        .await
+    }
+
     /// Updates the worktrees associated with the given project.
     pub async fn update_project(
         &self,
@@ -132,6 +132,7 @@ impl Database {
                 location_kind: ActiveValue::NotSet,
                 location_project_id: ActiveValue::NotSet,
                 initial_project_id: ActiveValue::NotSet,
+                is_observer: ActiveValue::NotSet,
             }
             .insert(&*tx)
             .await?;
@@ -162,7 +163,9 @@ impl Database {
                 .ok_or_else(|| anyhow!("user is not in the room"))?;
 
             let called_user_role = match caller.role.unwrap_or(ChannelRole::Member) {
-                ChannelRole::Admin | ChannelRole::Member => ChannelRole::Member,
+                ChannelRole::Admin | ChannelRole::CoHost | ChannelRole::Member => {
+                    ChannelRole::Member
+                }
                 ChannelRole::Guest | ChannelRole::Talker => ChannelRole::Guest,
                 ChannelRole::Banned => return Err(anyhow!("banned users cannot invite").into()),
             };
@@ -185,6 +188,7 @@ impl Database {
                 answering_connection_server_id: ActiveValue::NotSet,
                 location_kind: ActiveValue::NotSet,
                 location_project_id: ActiveValue::NotSet,
+                is_observer: ActiveValue::NotSet,
             }
             .insert(&*tx)
             .await?;
@@ -454,6 +458,7 @@ impl Database {
                 location_kind: ActiveValue::NotSet,
                 location_project_id: ActiveValue::NotSet,
                 initial_project_id: ActiveValue::NotSet,
+                is_observer: ActiveValue::NotSet,
             })
             .exec(tx)
             .await?;
@@ -1012,6 +1017,45 @@ impl Database {
         .await
     }
 
+    /// Joins or leaves observer mode for the calling connection's participant row. See
+    /// [`proto::SetParticipantObserverMode`].
+    pub async fn update_room_participant_observer_mode(
+        &self,
+        room_id: RoomId,
+        connection: ConnectionId,
+        is_observer: bool,
+    ) -> Result<TransactionGuard<proto::Room>> {
+        self.room_transaction(room_id, |tx| async move {
+            let result = room_participant::Entity::update_many()
+                .filter(
+                    Condition::all()
+                        .add(room_participant::Column::RoomId.eq(room_id))
+                        .add(
+                            room_participant::Column::AnsweringConnectionId
+                                .eq(connection.id as i32),
+                        )
+                        .add(
+                            room_participant::Column::AnsweringConnectionServerId
+                                .eq(connection.owner_id as i32),
+                        ),
+                )
+                .set(room_participant::ActiveModel {
+                    is_observer: ActiveValue::set(is_observer),
+                    ..Default::default()
+                })
+                .exec(&*tx)
+                .await?;
+
+            if result.rows_affected == 1 {
+                let room = self.get_room(room_id, &tx).await?;
+                Ok(room)
+            } else {
+                Err(anyhow!("could not update room participant observer mode"))?
+            }
+        })
+        .await
+    }
+
     /// Sets the role of a participant in the given room.
     pub async fn set_room_participant_role(
         &self,
@@ -1058,6 +1102,96 @@ impl Database {
         .await
     }
 
+    /// Checks that `moderator_id` is the host or a co-host of the room, then returns the
+    /// live connection `user_id` is answering the call from.
+    async fn moderatable_participant_connection(
+        &self,
+        moderator_id: UserId,
+        room_id: RoomId,
+        user_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<ConnectionId> {
+        let moderator = room_participant::Entity::find()
+            .filter(
+                Condition::all()
+                    .add(room_participant::Column::RoomId.eq(room_id))
+                    .add(room_participant::Column::UserId.eq(moderator_id)),
+            )
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("you are not in this room"))?;
+        if !moderator.role.unwrap_or(ChannelRole::Member).can_moderate_room() {
+            Err(anyhow!("only the host or a co-host can do this"))?;
+        }
+
+        room_participant::Entity::find()
+            .filter(
+                Condition::all()
+                    .add(room_participant::Column::RoomId.eq(room_id))
+                    .add(room_participant::Column::UserId.eq(user_id)),
+            )
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("user is not in this room"))?
+            .answering_connection()
+            .ok_or_else(|| anyhow!("user has not answered the call"))
+    }
+
+    /// Returns the connection that `user_id` is in the room from, so the caller can kick it,
+    /// provided `moderator_id` is the host or a co-host.
+    pub async fn remove_room_participant(
+        &self,
+        moderator_id: UserId,
+        room_id: RoomId,
+        user_id: UserId,
+    ) -> Result<TransactionGuard<ConnectionId>> {
+        self.room_transaction(room_id, |tx| async move {
+            self.moderatable_participant_connection(moderator_id, room_id, user_id, &tx)
+                .await
+        })
+        .await
+    }
+
+    /// Returns the room's LiveKit room name and `user_id`'s current connection id, so the
+    /// caller can mute their microphone and notify them they've been force-muted, provided
+    /// `moderator_id` is the host or a co-host.
+    pub async fn mute_room_participant(
+        &self,
+        moderator_id: UserId,
+        room_id: RoomId,
+        user_id: UserId,
+    ) -> Result<TransactionGuard<(String, ConnectionId)>> {
+        self.room_transaction(room_id, |tx| async move {
+            let target_connection_id = self
+                .moderatable_participant_connection(moderator_id, room_id, user_id, &tx)
+                .await?;
+            let live_kit_room = room::Entity::find_by_id(room_id)
+                .one(&tx)
+                .await?
+                .ok_or_else(|| anyhow!("could not find room"))?
+                .live_kit_room;
+            Ok((live_kit_room, target_connection_id))
+        })
+        .await
+    }
+
+    /// Like `mute_room_participant`, but doesn't touch LiveKit permissions - the target decides
+    /// whether to actually mute. Just checks moderator standing and returns who to notify.
+    pub async fn request_mute_room_participant(
+        &self,
+        moderator_id: UserId,
+        room_id: RoomId,
+        user_id: UserId,
+    ) -> Result<TransactionGuard<ConnectionId>> {
+        self.room_transaction(room_id, |tx| async move {
+            let target_connection_id = self
+                .moderatable_participant_connection(moderator_id, room_id, user_id, &tx)
+                .await?;
+            Ok(target_connection_id)
+        })
+        .await
+    }
+
     async fn check_user_has_signed_cla(
         &self,
         user_id: UserId,
@@ -1159,6 +1293,7 @@ impl Database {
                     .find(|project| project.id == initial_project_id)
                     .cloned()
             }),
+            context: None,
         })
     }
 
@@ -1259,6 +1394,11 @@ impl Database {
                         location: Some(proto::ParticipantLocation { variant: location }),
                         participant_index: participant_index as u32,
                         role: db_participant.role.unwrap_or(ChannelRole::Member).into(),
+                        platform: None,
+                        mic_state: None,
+                        is_observer: db_participant.is_observer,
+                        region: None,
+                        network_type: None,
                     },
                 );
             } else {
@@ -1283,6 +1423,7 @@ impl Database {
                 participant.projects.push(proto::ParticipantProject {
                     id: db_project.id.to_proto(),
                     worktree_root_names: Default::default(),
+                    read_only: db_project.read_only,
                 });
                 let project = participant.projects.last_mut().unwrap();
 
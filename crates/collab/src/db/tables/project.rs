@@ -12,6 +12,7 @@ pub struct Model {
     pub host_user_id: Option<UserId>,
     pub host_connection_id: Option<i32>,
     pub host_connection_server_id: Option<ServerId>,
+    pub read_only: bool,
 }
 
 impl Model {
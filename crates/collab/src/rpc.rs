@@ -85,6 +85,26 @@ const MESSAGE_COUNT_PER_PAGE: usize = 100;
 const MAX_MESSAGE_LEN: usize = 1024;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
 
+/// Overrides the `protocol_version` reported in `JoinRoomResponse`, to exercise a client
+/// rejecting an incompatible server without actually standing up two different
+/// `rpc::PROTOCOL_VERSION`s.
+#[cfg(any(test, feature = "test-support"))]
+static JOIN_ROOM_RESPONSE_PROTOCOL_VERSION_OVERRIDE: util::test::TestOverride =
+    util::test::TestOverride::new();
+
+#[cfg(any(test, feature = "test-support"))]
+pub fn set_join_room_response_protocol_version_for_test(version: u32) {
+    JOIN_ROOM_RESPONSE_PROTOCOL_VERSION_OVERRIDE.set(version as u64);
+}
+
+fn join_room_response_protocol_version() -> u32 {
+    #[cfg(any(test, feature = "test-support"))]
+    if let Some(override_version) = JOIN_ROOM_RESPONSE_PROTOCOL_VERSION_OVERRIDE.get() {
+        return override_version as u32;
+    }
+    rpc::PROTOCOL_VERSION
+}
+
 type MessageHandler =
     Box<dyn Send + Sync + Fn(Box<dyn AnyTypedEnvelope>, Session) -> BoxFuture<'static, ()>>;
 
@@ -280,10 +300,16 @@ impl Server {
             .add_request_handler(rejoin_room)
             .add_request_handler(leave_room)
             .add_request_handler(set_room_participant_role)
+            .add_request_handler(remove_room_participant)
+            .add_request_handler(mute_room_participant)
+            .add_request_handler(request_mute_room_participant)
             .add_request_handler(call)
             .add_request_handler(cancel_call)
             .add_message_handler(decline_call)
             .add_request_handler(update_participant_location)
+            .add_message_handler(update_participant_activity)
+            .add_request_handler(update_participant_observer_mode)
+            .add_request_handler(set_project_access)
             .add_request_handler(share_project)
             .add_message_handler(unshare_project)
             .add_request_handler(join_project)
@@ -1168,7 +1194,15 @@ async fn connection_lost(
         _ = executor.sleep(RECONNECT_TIMEOUT).fuse() => {
 
             log::info!("connection lost, removing all resources for user:{}, connection:{:?}", session.user_id(), session.connection_id);
-            leave_room_for_session(&session, session.connection_id).await.trace_err();
+            leave_room_for_session(
+                &session,
+                session.connection_id,
+                session.user_id(),
+                None,
+                proto::LeaveReason::Disconnected,
+            )
+            .await
+            .trace_err();
             leave_channel_buffers_for_session(&session)
                 .await
                 .trace_err();
@@ -1252,6 +1286,36 @@ async fn join_room(
         return join_channel_internal(channel_id, Box::new(response), session).await;
     }
 
+    // If the user is already connected to a room (this one or another) from a different
+    // session - e.g. they joined from another device - reclaim that connection's slot and tell
+    // it why, rather than letting the join below fail with "already joined".
+    if let Some(connection) = session
+        .db()
+        .await
+        .stale_room_connection(session.user_id())
+        .await?
+    {
+        if connection != session.connection_id {
+            session
+                .peer
+                .send(
+                    connection,
+                    proto::SessionSuperseded {
+                        reason: "you joined this room from another session".into(),
+                    },
+                )
+                .trace_err();
+            leave_room_for_session(
+                &session,
+                connection,
+                session.user_id(),
+                None,
+                proto::LeaveReason::Kicked,
+            )
+            .await?;
+        }
+    }
+
     let joined_room = {
         let room = session
             .db()
@@ -1299,6 +1363,7 @@ async fn join_room(
         room: Some(joined_room.room),
         channel_id: None,
         live_kit_connection_info,
+        protocol_version: Some(join_room_response_protocol_version()),
     })?;
 
     update_user_contacts(session.user_id(), &session).await?;
@@ -1482,11 +1547,18 @@ fn notify_rejoined_projects(
 
 /// leave room disconnects from the room.
 async fn leave_room(
-    _: proto::LeaveRoom,
+    request: proto::LeaveRoom,
     response: Response<proto::LeaveRoom>,
     session: Session,
 ) -> Result<()> {
-    leave_room_for_session(&session, session.connection_id).await?;
+    leave_room_for_session(
+        &session,
+        session.connection_id,
+        session.user_id(),
+        request.farewell_message,
+        proto::LeaveReason::Intentional,
+    )
+    .await?;
     response.send(proto::Ack {})?;
     Ok(())
 }
@@ -1539,6 +1611,119 @@ async fn set_room_participant_role(
     Ok(())
 }
 
+/// Kicks a participant out of the room, on behalf of the host or a co-host.
+async fn remove_room_participant(
+    request: proto::RemoveRoomParticipant,
+    response: Response<proto::RemoveRoomParticipant>,
+    session: Session,
+) -> Result<()> {
+    let removed_user_id = UserId::from_proto(request.user_id);
+    let connection_id = *session
+        .db()
+        .await
+        .remove_room_participant(
+            session.user_id(),
+            RoomId::from_proto(request.room_id),
+            removed_user_id,
+        )
+        .await?;
+
+    leave_room_for_session(
+        &session,
+        connection_id,
+        removed_user_id,
+        None,
+        proto::LeaveReason::Kicked,
+    )
+    .await?;
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// Forces a participant's microphone off (or restores it), on behalf of the host or a
+/// co-host, without changing their role.
+async fn mute_room_participant(
+    request: proto::MuteRoomParticipant,
+    response: Response<proto::MuteRoomParticipant>,
+    session: Session,
+) -> Result<()> {
+    let (live_kit_room, target_connection_id) = session
+        .db()
+        .await
+        .mute_room_participant(
+            session.user_id(),
+            RoomId::from_proto(request.room_id),
+            UserId::from_proto(request.user_id),
+        )
+        .await?
+        .clone();
+
+    if let Some(live_kit) = session.app_state.live_kit_client.as_ref() {
+        let can_publish = !request.muted;
+        live_kit
+            .update_participant(
+                live_kit_room,
+                request.user_id.to_string(),
+                live_kit_server::proto::ParticipantPermission {
+                    can_subscribe: true,
+                    can_publish,
+                    can_publish_data: can_publish,
+                    hidden: false,
+                    recorder: false,
+                },
+            )
+            .await
+            .trace_err();
+    }
+
+    session
+        .peer
+        .send(
+            target_connection_id,
+            proto::ForceMute {
+                room_id: request.room_id,
+                muted: request.muted,
+            },
+        )
+        .trace_err();
+
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// Asks a participant to mute themselves, on behalf of the host or a co-host, without forcing
+/// it the way `mute_room_participant` does - the target's client decides whether to comply.
+async fn request_mute_room_participant(
+    request: proto::RequestMuteRoomParticipant,
+    response: Response<proto::RequestMuteRoomParticipant>,
+    session: Session,
+) -> Result<()> {
+    let target_connection_id = session
+        .db()
+        .await
+        .request_mute_room_participant(
+            session.user_id(),
+            RoomId::from_proto(request.room_id),
+            UserId::from_proto(request.user_id),
+        )
+        .await?
+        .clone();
+
+    session
+        .peer
+        .send(
+            target_connection_id,
+            proto::RequestMute {
+                room_id: request.room_id,
+                requested_by: Some(session.connection_id.into()),
+            },
+        )
+        .trace_err();
+
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 /// Call someone else into the current room
 async fn call(
     request: proto::Call,
@@ -1572,7 +1757,9 @@ async fn call(
             )
             .await?;
         room_updated(room, &session.peer);
-        mem::take(incoming_call)
+        let mut incoming_call = mem::take(incoming_call);
+        incoming_call.context = request.context.clone();
+        incoming_call
     };
     update_user_contacts(called_user_id, &session).await?;
 
@@ -1699,6 +1886,69 @@ async fn update_participant_location(
     Ok(())
 }
 
+/// Relays a lightweight "I'm actively editing" ping to the rest of the room, without touching
+/// the database - unlike [`update_participant_location`], there's nothing here a reconnecting
+/// client would need to catch up on, so a dropped ping just means that peer's activity
+/// indicator decays a little early.
+async fn update_participant_activity(
+    request: proto::UpdateParticipantActivity,
+    session: Session,
+) -> Result<()> {
+    let room_id = RoomId::from_proto(request.room_id);
+    let connection_ids = session
+        .db()
+        .await
+        .room_connection_ids(room_id, session.connection_id)
+        .await?;
+
+    for connection_id in connection_ids.iter().cloned() {
+        if connection_id != session.connection_id {
+            session
+                .peer
+                .forward_send(session.connection_id, connection_id, request.clone())?;
+        }
+    }
+    Ok(())
+}
+
+async fn update_participant_observer_mode(
+    request: proto::SetParticipantObserverMode,
+    response: Response<proto::SetParticipantObserverMode>,
+    session: Session,
+) -> Result<()> {
+    let room_id = RoomId::from_proto(request.room_id);
+    let room = session
+        .db()
+        .await
+        .update_room_participant_observer_mode(
+            room_id,
+            session.connection_id,
+            request.is_observer,
+        )
+        .await?;
+
+    room_updated(&room, &session.peer);
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+async fn set_project_access(
+    request: proto::SetProjectAccess,
+    response: Response<proto::SetProjectAccess>,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let room = session
+        .db()
+        .await
+        .set_project_access(project_id, session.connection_id, request.read_only)
+        .await?;
+
+    room_updated(&room, &session.peer);
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 /// Share a project into the room.
 async fn share_project(
     request: proto::ShareProject,
@@ -3074,7 +3324,14 @@ async fn join_channel_internal(
                 "cleaning up stale connection",
             );
             drop(db);
-            leave_room_for_session(&session, connection).await?;
+            leave_room_for_session(
+                &session,
+                connection,
+                session.user_id(),
+                None,
+                proto::LeaveReason::Disconnected,
+            )
+            .await?;
             db = session.db().await;
         }
 
@@ -3124,6 +3381,7 @@ async fn join_channel_internal(
                 .as_ref()
                 .map(|channel| channel.id.to_proto()),
             live_kit_connection_info,
+            protocol_version: Some(join_room_response_protocol_version()),
         })?;
 
         let mut connection_pool = session.connection_pool().await;
@@ -4295,7 +4553,13 @@ async fn update_user_contacts(user_id: UserId, session: &Session) -> Result<()>
     Ok(())
 }
 
-async fn leave_room_for_session(session: &Session, connection_id: ConnectionId) -> Result<()> {
+async fn leave_room_for_session(
+    session: &Session,
+    connection_id: ConnectionId,
+    leaving_user_id: UserId,
+    farewell_message: Option<String>,
+    leave_reason: proto::LeaveReason,
+) -> Result<()> {
     let mut contacts_to_update = HashSet::default();
 
     let room_id;
@@ -4306,7 +4570,7 @@ async fn leave_room_for_session(session: &Session, connection_id: ConnectionId)
     let channel;
 
     if let Some(mut left_room) = session.db().await.leave_room(connection_id).await? {
-        contacts_to_update.insert(session.user_id());
+        contacts_to_update.insert(leaving_user_id);
 
         for project in left_room.left_projects.values() {
             project_left(project, session);
@@ -4320,6 +4584,24 @@ async fn leave_room_for_session(session: &Session, connection_id: ConnectionId)
         channel = mem::take(&mut left_room.channel);
 
         room_updated(&room, &session.peer);
+        if farewell_message.is_some() || leave_reason != proto::LeaveReason::Unknown {
+            broadcast(
+                None,
+                room.participants
+                    .iter()
+                    .filter_map(|participant| Some(participant.peer_id?.into())),
+                |peer_id| {
+                    session.peer.send(
+                        peer_id,
+                        proto::ParticipantLeft {
+                            peer_id: Some(connection_id.into()),
+                            farewell_message: farewell_message.clone(),
+                            leave_reason: leave_reason as i32,
+                        },
+                    )
+                },
+            );
+        }
     } else {
         return Ok(());
     }
@@ -4357,7 +4639,7 @@ async fn leave_room_for_session(session: &Session, connection_id: ConnectionId)
 
     if let Some(live_kit) = session.app_state.live_kit_client.as_ref() {
         live_kit
-            .remove_participant(live_kit_room.clone(), session.user_id().to_string())
+            .remove_participant(live_kit_room.clone(), leaving_user_id.to_string())
             .await
             .trace_err();
 
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use call::Room;
+use call::{ActiveCall, Room};
 use client::ChannelId;
 use gpui::{Model, TestAppContext};
 
@@ -52,6 +52,39 @@ fn channel_id(room: &Model<Room>, cx: &mut TestAppContext) -> Option<ChannelId>
     cx.read(|cx| room.read(cx).channel_id())
 }
 
+/// Connects `user_a` and `user_b` as contacts, has `user_a` call `user_b`, and has `user_b`
+/// accept, returning both clients' rooms once the call has settled.
+async fn join_room_for_test_pair(
+    server: &mut TestServer,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) -> (TestClient, TestClient, Model<Room>, Model<Room>) {
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    cx_a.executor().run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    cx_a.executor().run_until_parked();
+
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+    (client_a, client_b, room_a, room_b)
+}
+
 fn rust_lang() -> Arc<Language> {
     Arc::new(Language::new(
         LanguageConfig {
@@ -1,13 +1,17 @@
 use crate::{
-    rpc::{CLEANUP_TIMEOUT, RECONNECT_TIMEOUT},
+    rpc::{set_join_room_response_protocol_version_for_test, CLEANUP_TIMEOUT, RECONNECT_TIMEOUT},
     tests::{
-        channel_id, following_tests::join_channel, room_participants, rust_lang, RoomParticipants,
-        TestClient, TestServer,
+        channel_id, following_tests::join_channel, join_room_for_test_pair, room_participants,
+        rust_lang, RoomParticipants, TestClient, TestServer,
     },
 };
 use anyhow::{anyhow, Result};
 use assistant::{ContextStore, PromptBuilder, SlashCommandWorkingSet, ToolWorkingSet};
-use call::{room, ActiveCall, ParticipantLocation, Room};
+use call::{
+    call_settings::CallSettings, room, ActiveCall, CallDecision, CallResponseSummary,
+    ConnectionSummary, LeaveConfirmation, ParticipantLocation, ResolvedLocation, Room,
+    VideoQuality, ViewAnchor,
+};
 use client::{User, RECEIVE_TIMEOUT};
 use collections::{HashMap, HashSet};
 use fs::{FakeFs, Fs as _, RemoveOptions};
@@ -33,8 +37,9 @@ use project::{
     HoverBlockKind, Project, ProjectPath,
 };
 use rand::prelude::*;
+use rpc::proto;
 use serde_json::json;
-use settings::SettingsStore;
+use settings::{Settings, SettingsStore};
 use std::{
     cell::{Cell, RefCell},
     env, future, mem,
@@ -488,6 +493,75 @@ async fn test_calling_multiple_users_simultaneously(
     );
 }
 
+#[gpui::test]
+async fn test_accept_incoming_renders_prefetched_room_before_join_resolves(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+
+    let mut incoming_call_b = active_call_b.read_with(cx_b, |call, _| call.incoming());
+    executor.run_until_parked();
+    let call_b = incoming_call_b.next().await.unwrap().unwrap();
+
+    // A stand-in for a roster fetched out-of-band (e.g. from a list-rooms response), with an
+    // obviously-synthetic `participant_index` that the real `JoinRoom` response would never
+    // assign, so we can tell whether we're looking at the prefetched roster or the real one.
+    let prefetched_room = proto::Room {
+        id: call_b.room_id,
+        participants: vec![proto::Participant {
+            user_id: client_a.user_id().unwrap(),
+            peer_id: Some(client_a.peer_id().unwrap()),
+            projects: Vec::new(),
+            location: None,
+            participant_index: 999,
+            role: proto::ChannelRole::Member as i32,
+            platform: None,
+            is_observer: false,
+            mic_state: None,
+            region: None,
+            network_type: None,
+        }],
+        ..Default::default()
+    };
+
+    active_call_b
+        .update(cx_b, |call, cx| {
+            call.accept_incoming_with_prefetched_room(prefetched_room, cx)
+        })
+        .await
+        .unwrap();
+
+    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+    room_b.read_with(cx_b, |room, _| {
+        let participant = &room.remote_participants()[&client_a.user_id().unwrap()];
+        assert_eq!(participant.participant_index.0, 999);
+    });
+
+    // Once the real `JoinRoom` response arrives, it reconciles over the prefetched roster.
+    executor.run_until_parked();
+    room_b.read_with(cx_b, |room, _| {
+        let participant = &room.remote_participants()[&client_a.user_id().unwrap()];
+        assert_ne!(participant.participant_index.0, 999);
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_joining_channels_and_calling_multiple_users_simultaneously(
     executor: BackgroundExecutor,
@@ -1178,6 +1252,56 @@ async fn test_server_restarts(
     assert!(incoming_call_d.next().await.unwrap().is_none());
 }
 
+#[gpui::test]
+async fn test_duplicate_local_session_is_superseded(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b1: &mut TestAppContext,
+    cx_b2: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b1 = server.create_client(cx_b1, "user_b").await;
+    let _client_b2 = server.create_client(cx_b2, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b1, cx_b1)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b1 = cx_b1.read(ActiveCall::global);
+    let active_call_b2 = cx_b2.read(ActiveCall::global);
+    let events_b1 = active_call_events(cx_b1);
+
+    let mut incoming_call_b1 = active_call_b1.read_with(cx_b1, |call, _| call.incoming());
+    let mut incoming_call_b2 = active_call_b2.read_with(cx_b2, |call, _| call.incoming());
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b1.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(incoming_call_b1.next().await.unwrap().is_some());
+    assert!(incoming_call_b2.next().await.unwrap().is_some());
+
+    // Both of user B's sessions race to accept the same call, ending up in the same room.
+    let accept_b1 = active_call_b1.update(cx_b1, |call, cx| call.accept_incoming(cx));
+    let accept_b2 = active_call_b2.update(cx_b2, |call, cx| call.accept_incoming(cx));
+    executor.run_until_parked();
+    accept_b1.await.unwrap();
+    accept_b2.await.unwrap();
+
+    // The session that the server saw join second keeps the room; the other one is told its
+    // session was superseded and drops out of the call.
+    assert!(active_call_b1.read_with(cx_b1, |call, _| call.room().is_none()));
+    assert!(active_call_b2.read_with(cx_b2, |call, _| call.room().is_some()));
+    assert!(events_b1.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::SessionSuperseded { reason } if !reason.is_empty()
+    )));
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_calls_on_multiple_connections(
     executor: BackgroundExecutor,
@@ -1887,6 +2011,61 @@ async fn test_active_call_events(
     );
 }
 
+#[gpui::test]
+async fn test_follow_target_lost_project(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let peer_id_a = room_b.read_with(cx_b, |room, _| {
+        room.remote_participants()
+            .values()
+            .find(|p| p.user.github_login == "user_a")
+            .unwrap()
+            .peer_id
+    });
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let project_a = client_a.build_empty_local_project(cx_a);
+    let project_a_id = room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // Stub out the leader-side follow handler that normally lives on `Workspace`.
+    let _handle_follow = client_a.client().add_request_handler(
+        room_a.downgrade(),
+        |_, _: client::TypedEnvelope<proto::Follow>, _| async { Ok(proto::FollowResponse::default()) },
+    );
+
+    client_b
+        .client()
+        .request(proto::Follow {
+            room_id: room_b.read_with(cx_b, |room, _| room.id()),
+            project_id: Some(project_a_id),
+            leader_id: peer_id_a,
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let events_b = active_call_events(cx_b);
+    cx_a.read(ActiveCall::global)
+        .update(cx_a, |call, cx| call.unshare_project(project_a, cx))
+        .unwrap();
+    executor.run_until_parked();
+
+    assert!(events_b.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::FollowTargetLostProject { peer_id, project_id }
+            if *peer_id == peer_id_a && *project_id == project_a_id
+    )));
+}
+
 fn active_call_events(cx: &mut TestAppContext) -> Rc<RefCell<Vec<room::Event>>> {
     let events = Rc::new(RefCell::new(Vec::new()));
     let active_call = cx.read(ActiveCall::global);
@@ -1903,183 +2082,4057 @@ fn active_call_events(cx: &mut TestAppContext) -> Rc<RefCell<Vec<room::Event>>>
 }
 
 #[gpui::test]
-async fn test_mute_deafen(
+async fn test_participant_activity_signal(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.active_editors(), Vec::new());
+    });
+
+    room_b.update(cx_b, |room, _| room.report_activity());
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.active_editors(), vec![client_b.peer_id().unwrap()]);
+    });
+}
+
+#[gpui::test]
+async fn test_large_initial_roster_is_applied_in_chunks(
     executor: BackgroundExecutor,
     cx_a: &mut TestAppContext,
     cx_b: &mut TestAppContext,
     cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
 ) {
+    // Lower the thresholds so joining a room with only three other participants already exercises
+    // chunking, one participant per chunk, without having to spin up dozens of real clients.
+    room::set_initial_roster_chunk_threshold_for_test(2);
+    room::set_initial_roster_chunk_size_for_test(1);
+
     let mut server = TestServer::start(executor.clone()).await;
-    let client_a = server.create_client(cx_a, "user_a").await;
+    let _client_a = server.create_client(cx_a, "user_a").await;
     let client_b = server.create_client(cx_b, "user_b").await;
     let client_c = server.create_client(cx_c, "user_c").await;
-
+    let client_d = server.create_client(cx_d, "user_d").await;
     server
-        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .make_contacts(&mut [
+            (&_client_a, cx_a),
+            (&client_b, cx_b),
+            (&client_c, cx_c),
+            (&client_d, cx_d),
+        ])
         .await;
 
     let active_call_a = cx_a.read(ActiveCall::global);
     let active_call_b = cx_b.read(ActiveCall::global);
     let active_call_c = cx_c.read(ActiveCall::global);
+    let active_call_d = cx_d.read(ActiveCall::global);
 
-    // User A calls user B, B answers.
+    // Get B and C into the room first, so D's initial roster already has three participants in it
+    // (A, B and C).
     active_call_a
         .update(cx_a, |call, cx| {
             call.invite(client_b.user_id().unwrap(), None, cx)
         })
         .await
         .unwrap();
-    executor.run_until_parked();
     active_call_b
         .update(cx_b, |call, cx| call.accept_incoming(cx))
         .await
         .unwrap();
     executor.run_until_parked();
 
-    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
-    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
 
-    room_a.read_with(cx_a, |room, _| assert!(!room.is_muted()));
-    room_b.read_with(cx_b, |room, _| assert!(!room.is_muted()));
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_d.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_d
+        .update(cx_d, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
 
-    // Users A and B are both muted.
-    assert_eq!(
-        participant_audio_state(&room_a, cx_a),
-        &[ParticipantAudioState {
-            user_id: client_b.user_id().unwrap(),
-            is_muted: false,
-            audio_tracks_playing: vec![true],
-        }]
-    );
-    assert_eq!(
-        participant_audio_state(&room_b, cx_b),
-        &[ParticipantAudioState {
-            user_id: client_a.user_id().unwrap(),
-            is_muted: false,
-            audio_tracks_playing: vec![true],
-        }]
-    );
+    // `accept_incoming` resolves as soon as D's (still-empty) room exists, before the chunked
+    // roster has actually landed - subscribe now so we see every chunk as it's applied.
+    let room_d = active_call_d.read_with(cx_d, |call, _| call.room().unwrap().clone());
+    let events = Rc::new(RefCell::new(Vec::new()));
+    cx_d.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(&room_d, move |_, event, _| events.borrow_mut().push(event.clone()))
+            .detach()
+    });
 
-    // User A mutes
-    room_a.update(cx_a, |room, cx| room.toggle_mute(cx));
     executor.run_until_parked();
 
-    // User A hears user B, but B doesn't hear A.
-    room_a.read_with(cx_a, |room, _| assert!(room.is_muted()));
-    room_b.read_with(cx_b, |room, _| assert!(!room.is_muted()));
-    assert_eq!(
-        participant_audio_state(&room_a, cx_a),
-        &[ParticipantAudioState {
-            user_id: client_b.user_id().unwrap(),
-            is_muted: false,
-            audio_tracks_playing: vec![true],
-        }]
-    );
+    let events = events.borrow();
+    let joined = events
+        .iter()
+        .filter(|event| matches!(event, room::Event::ParticipantJoined { .. }))
+        .count();
+    assert_eq!(joined, 3, "expected one ParticipantJoined per chunk");
     assert_eq!(
-        participant_audio_state(&room_b, cx_b),
-        &[ParticipantAudioState {
-            user_id: client_a.user_id().unwrap(),
-            is_muted: true,
-            audio_tracks_playing: vec![true],
-        }]
+        events.last(),
+        Some(&room::Event::RosterComplete),
+        "RosterComplete should fire last, once every chunk has landed"
     );
 
-    // User A deafens
-    room_a.update(cx_a, |room, cx| room.toggle_deafen(cx));
+    room::set_initial_roster_chunk_threshold_for_test(room::INITIAL_ROSTER_CHUNK_THRESHOLD);
+    room::set_initial_roster_chunk_size_for_test(room::INITIAL_ROSTER_CHUNK_SIZE);
+}
+
+#[gpui::test]
+async fn test_simultaneous_joins_emit_a_batch_event(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let _client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let client_d = server.create_client(cx_d, "user_d").await;
+    server
+        .make_contacts(&mut [
+            (&_client_a, cx_a),
+            (&client_b, cx_b),
+            (&client_c, cx_c),
+            (&client_d, cx_d),
+        ])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+    let active_call_d = cx_d.read(ActiveCall::global);
+
+    // Get B and C into the room first, so D's initial roster lands A, B and C in a single
+    // update (the default chunk threshold is well above three participants).
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
     executor.run_until_parked();
 
-    // User A does not hear user B.
-    room_a.read_with(cx_a, |room, _| assert!(room.is_muted()));
-    room_b.read_with(cx_b, |room, _| assert!(!room.is_muted()));
-    assert_eq!(
-        participant_audio_state(&room_a, cx_a),
-        &[ParticipantAudioState {
-            user_id: client_b.user_id().unwrap(),
-            is_muted: false,
-            audio_tracks_playing: vec![false],
-        }]
-    );
-    assert_eq!(
-        participant_audio_state(&room_b, cx_b),
-        &[ParticipantAudioState {
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_d.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_d
+        .update(cx_d, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+
+    // `accept_incoming` resolves as soon as D's (still-empty) room exists, before its initial
+    // roster has actually landed - subscribe now so we see it as it's applied.
+    let room_d = active_call_d.read_with(cx_d, |call, _| call.room().unwrap().clone());
+    let events = Rc::new(RefCell::new(Vec::new()));
+    cx_d.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(&room_d, move |_, event, _| events.borrow_mut().push(event.clone()))
+            .detach()
+    });
+
+    executor.run_until_parked();
+
+    let events = events.borrow();
+    let joined = events
+        .iter()
+        .filter(|event| matches!(event, room::Event::ParticipantJoined { .. }))
+        .count();
+    assert_eq!(
+        joined, 3,
+        "individual ParticipantJoined events should still fire for each participant"
+    );
+
+    let batches: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            room::Event::ParticipantsJoinedBatch { peer_ids } => Some(peer_ids.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        batches.len(),
+        1,
+        "A, B and C landing together should coalesce into a single batch event"
+    );
+    assert_eq!(batches[0].len(), 3);
+}
+
+#[gpui::test]
+async fn test_join_room_with_incompatible_protocol_version_fails(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // The server reports a protocol version the client doesn't understand - `accept_incoming`
+    // should fail the join rather than hand back a `Room` that might misinterpret updates.
+    set_join_room_response_protocol_version_for_test(rpc::PROTOCOL_VERSION + 1);
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap_err();
+    active_call_b.read_with(cx_b, |call, _| assert!(call.room().is_none()));
+    set_join_room_response_protocol_version_for_test(rpc::PROTOCOL_VERSION);
+}
+
+#[gpui::test]
+async fn test_mute_deafen(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+
+    // User A calls user B, B answers.
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+
+    room_a.read_with(cx_a, |room, _| assert!(!room.is_muted()));
+    room_b.read_with(cx_b, |room, _| assert!(!room.is_muted()));
+
+    // Users A and B are both muted.
+    assert_eq!(
+        participant_audio_state(&room_a, cx_a),
+        &[ParticipantAudioState {
+            user_id: client_b.user_id().unwrap(),
+            is_muted: false,
+            audio_tracks_playing: vec![true],
+        }]
+    );
+    assert_eq!(
+        participant_audio_state(&room_b, cx_b),
+        &[ParticipantAudioState {
+            user_id: client_a.user_id().unwrap(),
+            is_muted: false,
+            audio_tracks_playing: vec![true],
+        }]
+    );
+
+    // User A mutes
+    room_a.update(cx_a, |room, cx| room.toggle_mute(cx));
+    executor.run_until_parked();
+
+    // User A hears user B, but B doesn't hear A.
+    room_a.read_with(cx_a, |room, _| assert!(room.is_muted()));
+    room_b.read_with(cx_b, |room, _| assert!(!room.is_muted()));
+    assert_eq!(
+        participant_audio_state(&room_a, cx_a),
+        &[ParticipantAudioState {
+            user_id: client_b.user_id().unwrap(),
+            is_muted: false,
+            audio_tracks_playing: vec![true],
+        }]
+    );
+    assert_eq!(
+        participant_audio_state(&room_b, cx_b),
+        &[ParticipantAudioState {
+            user_id: client_a.user_id().unwrap(),
+            is_muted: true,
+            audio_tracks_playing: vec![true],
+        }]
+    );
+
+    // User A deafens
+    room_a.update(cx_a, |room, cx| room.toggle_deafen(cx));
+    executor.run_until_parked();
+
+    // User A does not hear user B.
+    room_a.read_with(cx_a, |room, _| assert!(room.is_muted()));
+    room_b.read_with(cx_b, |room, _| assert!(!room.is_muted()));
+    assert_eq!(
+        participant_audio_state(&room_a, cx_a),
+        &[ParticipantAudioState {
+            user_id: client_b.user_id().unwrap(),
+            is_muted: false,
+            audio_tracks_playing: vec![false],
+        }]
+    );
+    assert_eq!(
+        participant_audio_state(&room_b, cx_b),
+        &[ParticipantAudioState {
             user_id: client_a.user_id().unwrap(),
             is_muted: true,
             audio_tracks_playing: vec![true],
         }]
     );
 
-    // User B calls user C, C joins.
-    active_call_b
-        .update(cx_b, |call, cx| {
+    // User B calls user C, C joins.
+    active_call_b
+        .update(cx_b, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // User A does not hear users B or C.
+    assert_eq!(
+        participant_audio_state(&room_a, cx_a),
+        &[
+            ParticipantAudioState {
+                user_id: client_b.user_id().unwrap(),
+                is_muted: false,
+                audio_tracks_playing: vec![false],
+            },
+            ParticipantAudioState {
+                user_id: client_c.user_id().unwrap(),
+                is_muted: false,
+                audio_tracks_playing: vec![false],
+            }
+        ]
+    );
+    assert_eq!(
+        participant_audio_state(&room_b, cx_b),
+        &[
+            ParticipantAudioState {
+                user_id: client_a.user_id().unwrap(),
+                is_muted: true,
+                audio_tracks_playing: vec![true],
+            },
+            ParticipantAudioState {
+                user_id: client_c.user_id().unwrap(),
+                is_muted: false,
+                audio_tracks_playing: vec![true],
+            }
+        ]
+    );
+
+    #[derive(PartialEq, Eq, Debug)]
+    struct ParticipantAudioState {
+        user_id: u64,
+        is_muted: bool,
+        audio_tracks_playing: Vec<bool>,
+    }
+
+    fn participant_audio_state(
+        room: &Model<Room>,
+        cx: &TestAppContext,
+    ) -> Vec<ParticipantAudioState> {
+        room.read_with(cx, |room, _| {
+            room.remote_participants()
+                .iter()
+                .map(|(user_id, participant)| ParticipantAudioState {
+                    user_id: *user_id,
+                    is_muted: participant.muted,
+                    audio_tracks_playing: participant
+                        .audio_tracks
+                        .values()
+                        .map(|track| track.is_playing())
+                        .collect(),
+                })
+                .collect::<Vec<_>>()
+        })
+    }
+}
+
+#[gpui::test]
+async fn test_mute_on_join(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    cx_b.update(|cx| {
+        CallSettings::override_global(
+            CallSettings {
+                mute_on_join: true,
+                share_on_join: false,
+            },
+            cx,
+        );
+    });
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+
+    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+    room_b.read_with(cx_b, |room, _| assert!(room.is_muted()));
+}
+
+#[gpui::test]
+async fn test_subscribe_with_replay(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    // Fire an event before anyone subscribes - a late subscriber should still see it via replay.
+    room_a
+        .update(cx_a, |room, cx| room.set_max_participants(Some(1), cx));
+    executor.run_until_parked();
+
+    let replayed_events = Rc::new(RefCell::new(Vec::new()));
+    let replayed_events_for_closure = replayed_events.clone();
+    let _subscription = room_b.update(cx_b, |room_b, cx| {
+        Room::subscribe_with_replay(&room_a, room_b, cx, move |_, _, event, _| {
+            replayed_events_for_closure.borrow_mut().push(event.clone());
+        })
+    });
+
+    assert_eq!(replayed_events.borrow().as_slice(), &[room::Event::RoomFull]);
+
+    // Live events still arrive as normal, on top of the replayed ones.
+    room_a
+        .update(cx_a, |room, cx| room.set_max_participants(None, cx));
+    executor.run_until_parked();
+
+    assert_eq!(
+        replayed_events.borrow().as_slice(),
+        &[room::Event::RoomFull, room::Event::RoomHasCapacity]
+    );
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_recently_departed(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+
+    room_a.update(cx_a, |room, _| assert!(room.recently_departed().is_empty()));
+
+    // User A loses connectivity, and while they're unreachable user C hangs up.
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+
+    let active_call_c = cx_c.read(ActiveCall::global);
+    active_call_c
+        .update(cx_c, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // User A's connection is restored and they rejoin before giving up.
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+
+    room_a.update(cx_a, |room, _| {
+        assert_eq!(room.recently_departed(), vec![client_c.user_id().unwrap()]);
+        // Reading the list clears it.
+        assert!(room.recently_departed().is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_set_audio_input_rejects_invalid_device(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.update(cx_a, |room, cx| {
+        room.set_audio_input("built-in-mic".to_string(), cx)
+    });
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.audio_input_device_id(), Some("built-in-mic"));
+    });
+
+    let events_a = active_call_events(cx_a);
+    room_a.update(cx_a, |room, cx| room.set_audio_input(String::new(), cx));
+    assert!(matches!(
+        events_a.borrow().as_slice(),
+        [room::Event::Error { .. }]
+    ));
+
+    // The previous, valid selection is left untouched.
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.audio_input_device_id(), Some("built-in-mic"));
+    });
+}
+
+#[gpui::test]
+async fn test_shared_projects_in_order(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let project_1 = client_a.build_empty_local_project(cx_a);
+    let project_2 = client_a.build_empty_local_project(cx_a);
+    let project_3 = client_a.build_empty_local_project(cx_a);
+
+    let id_1 = room_a
+        .update(cx_a, |room, cx| room.share_project(project_1.clone(), cx))
+        .await
+        .unwrap();
+    let id_2 = room_a
+        .update(cx_a, |room, cx| room.share_project(project_2.clone(), cx))
+        .await
+        .unwrap();
+    let id_3 = room_a
+        .update(cx_a, |room, cx| room.share_project(project_3.clone(), cx))
+        .await
+        .unwrap();
+
+    let shared_ids = |room_a: &Model<Room>, cx_a: &mut TestAppContext| {
+        room_a.read_with(cx_a, |room, cx| {
+            room.shared_projects_in_order()
+                .map(|project| project.read(cx).remote_id().unwrap())
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(shared_ids(&room_a, cx_a), vec![id_1, id_2, id_3]);
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.unshare_project(project_2, cx))
+        .unwrap();
+    assert_eq!(shared_ids(&room_a, cx_a), vec![id_1, id_3]);
+}
+
+#[gpui::test]
+async fn test_force_mute(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    room_a.read_with(cx_a, |room, _| assert!(room.can_moderate()));
+    room_b.read_with(cx_b, |room, _| assert!(!room.can_moderate()));
+
+    // The host force-mutes user B. User B can't unmute themselves while that's in effect.
+    room_a
+        .update(cx_a, |room, cx| {
+            room.mute_participant_remotely(client_b.user_id().unwrap(), true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.is_force_muted());
+        assert!(room.is_muted());
+    });
+    room_b.update(cx_b, |room, cx| {
+        room.unmute(cx).unwrap_err();
+    });
+    room_b.read_with(cx_b, |room, _| assert!(room.is_muted()));
+
+    // The ordinary mute toggle (what the mute button actually calls) is just as powerless
+    // against a force-mute as `unmute` is.
+    room_b.update(cx_b, |room, cx| room.toggle_mute(cx));
+    room_b.read_with(cx_b, |room, _| assert!(room.is_muted()));
+
+    // Once the host lifts the force-mute, user B can unmute themselves again.
+    room_a
+        .update(cx_a, |room, cx| {
+            room.mute_participant_remotely(client_b.user_id().unwrap(), false, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| assert!(!room.is_force_muted()));
+    room_b.update(cx_b, |room, cx| room.unmute(cx).unwrap());
+    room_b.read_with(cx_b, |room, _| assert!(!room.is_muted()));
+}
+
+#[gpui::test]
+async fn test_push_to_talk(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    room_a.read_with(cx_a, |room, _| assert!(!room.is_muted()));
+
+    // Enabling push-to-talk mutes immediately; holding the key unmutes, releasing it re-mutes.
+    room_a.update(cx_a, |room, cx| room.set_push_to_talk(true, cx));
+    executor.run_until_parked();
+    room_a.read_with(cx_a, |room, _| assert!(room.is_muted()));
+
+    room_a.update(cx_a, |room, cx| room.push_to_talk_begin(cx));
+    executor.run_until_parked();
+    room_a.read_with(cx_a, |room, _| assert!(!room.is_muted()));
+
+    room_a.update(cx_a, |room, cx| room.push_to_talk_end(cx));
+    executor.run_until_parked();
+    room_a.read_with(cx_a, |room, _| assert!(room.is_muted()));
+
+    // The host force-mutes user B - holding the push-to-talk key doesn't override that.
+    room_a
+        .update(cx_a, |room, cx| {
+            room.mute_participant_remotely(client_b.user_id().unwrap(), true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    room_b.read_with(cx_b, |room, _| assert!(room.is_force_muted()));
+
+    room_b.update(cx_b, |room, cx| room.set_push_to_talk(true, cx));
+    executor.run_until_parked();
+    room_b.update(cx_b, |room, cx| room.push_to_talk_begin(cx));
+    executor.run_until_parked();
+    room_b.read_with(cx_b, |room, _| assert!(room.is_muted()));
+}
+
+#[gpui::test]
+async fn test_mute_all_except(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let client_d = server.create_client(cx_d, "user_d").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_d, cx_d)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+    let active_call_d = cx_d.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_d.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_d
+        .update(cx_d, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let room_c = active_call_c.read_with(cx_c, |call, _| call.room().unwrap().clone());
+    let room_d = active_call_d.read_with(cx_d, |call, _| call.room().unwrap().clone());
+
+    room_a.read_with(cx_a, |room, _| assert!(room.can_moderate()));
+    room_b.read_with(cx_b, |room, _| assert!(!room.can_moderate()));
+
+    // The host mutes everyone except user D.
+    room_a
+        .update(cx_a, |room, cx| {
+            room.mute_all(&[client_d.peer_id().unwrap()], cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| assert!(room.is_force_muted()));
+    room_c.read_with(cx_c, |room, _| assert!(room.is_force_muted()));
+    room_d.read_with(cx_d, |room, _| assert!(!room.is_force_muted()));
+
+    // Non-hosts can't mute everyone.
+    room_b
+        .update(cx_b, |room, cx| room.mute_all(&[], cx))
+        .await
+        .unwrap_err();
+}
+
+#[gpui::test]
+async fn test_participant_color_is_stable_and_distinct(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // No color for our own peer id - `participant_color` only covers remote participants.
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room
+            .participant_color(client_a.peer_id().unwrap())
+            .is_none());
+    });
+
+    let color_b = room_a
+        .read_with(cx_a, |room, _| {
+            room.participant_color(client_b.peer_id().unwrap())
+        })
+        .unwrap();
+    let color_c = room_a
+        .read_with(cx_a, |room, _| {
+            room.participant_color(client_c.peer_id().unwrap())
+        })
+        .unwrap();
+    assert_ne!((color_b.h, color_b.s, color_b.l), (color_c.h, color_c.s, color_c.l));
+
+    // Asking again returns the exact same color for the same person.
+    let color_b_again = room_a
+        .read_with(cx_a, |room, _| {
+            room.participant_color(client_b.peer_id().unwrap())
+        })
+        .unwrap();
+    assert_eq!(
+        (color_b.h, color_b.s, color_b.l),
+        (color_b_again.h, color_b_again.s, color_b_again.l)
+    );
+}
+
+#[gpui::test]
+async fn test_publish_projects_concurrently(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let project_1 = client_a.build_empty_local_project(cx_a);
+    let project_2 = client_a.build_empty_local_project(cx_a);
+    let project_3 = client_a.build_empty_local_project(cx_a);
+
+    let ids = room_a
+        .update(cx_a, |room, cx| {
+            room.publish_projects(vec![project_1, project_2, project_3], cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids.len(), 3);
+
+    let shared_ids = room_a.read_with(cx_a, |room, cx| {
+        room.shared_projects_in_order()
+            .map(|project| project.read(cx).remote_id().unwrap())
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(shared_ids.len(), 3);
+    for id in ids {
+        assert!(shared_ids.contains(&id));
+    }
+}
+
+#[gpui::test]
+async fn test_observer_mode_rejects_publishing(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a
+        .update(cx_a, |room, cx| room.set_observer_mode(true, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.local_participant().is_observer);
+    });
+    room_a
+        .update(cx_a, |room, cx| room.share_microphone(cx))
+        .await
+        .unwrap_err();
+    room_a
+        .update(cx_a, |room, cx| room.share_screen(cx))
+        .await
+        .unwrap_err();
+
+    let peer_id_a = client_a.peer_id().unwrap();
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.peer_is_observer(peer_id_a));
+    });
+}
+
+#[gpui::test]
+async fn test_audit_log_records_join_and_leave(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, _| {
+        let entries = room.audit_log();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].user_id, client_b.user_id().unwrap());
+        assert_eq!(entries[0].kind, call::room::AuditEventKind::Joined);
+    });
+
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_b
+        .update(cx_b, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        let entries = room.audit_log();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, call::room::AuditEventKind::Joined);
+        assert_eq!(entries[1].user_id, client_b.user_id().unwrap());
+        assert_eq!(entries[1].kind, call::room::AuditEventKind::Left);
+    });
+}
+
+#[gpui::test]
+async fn test_set_location_coalesces_redundant_broadcasts(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a.fs().insert_tree("/a", json!({ "1.txt": "" })).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    room_a
+        .update(cx_a, |room, cx| room.set_location(Some(&project_a), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        room_a.read_with(cx_a, |room, _| room.location_broadcasts_sent_for_test()),
+        1
+    );
+
+    // Setting the exact same location again shouldn't issue a second `UpdateParticipantLocation`.
+    room_a
+        .update(cx_a, |room, cx| room.set_location(Some(&project_a), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        room_a.read_with(cx_a, |room, _| room.location_broadcasts_sent_for_test()),
+        1
+    );
+
+    room_a
+        .update(cx_a, |room, cx| room.set_location(None, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        room_a.read_with(cx_a, |room, _| room.location_broadcasts_sent_for_test()),
+        2
+    );
+}
+
+#[gpui::test]
+async fn test_set_foreground_broadcasts_external_and_restores(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a.fs().insert_tree("/a", json!({ "1.txt": "" })).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    room_a
+        .update(cx_a, |room, cx| room.set_location(Some(&project_a), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.remote_participant_for_peer_id(client_a.peer_id().unwrap())
+                .unwrap()
+                .location,
+            ParticipantLocation::SharedProject { project_id }
+        );
+    });
+
+    // Backgrounding A clears the broadcast location, even though A is still "in" the project.
+    room_a.update(cx_a, |room, cx| room.set_foreground(false, cx));
+    executor.run_until_parked();
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.remote_participant_for_peer_id(client_a.peer_id().unwrap())
+                .unwrap()
+                .location,
+            ParticipantLocation::External
+        );
+    });
+    room_a.read_with(cx_a, |room, _| assert!(!room.is_foreground()));
+
+    // Foregrounding restores the real location.
+    room_a.update(cx_a, |room, cx| room.set_foreground(true, cx));
+    executor.run_until_parked();
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.remote_participant_for_peer_id(client_a.peer_id().unwrap())
+                .unwrap()
+                .location,
+            ParticipantLocation::SharedProject { project_id }
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_leave_flushes_pending_location_broadcast(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a.fs().insert_tree("/a", json!({ "1.txt": "" })).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    // Leave immediately, without ever awaiting `set_location`'s returned task - the broadcast
+    // it kicked off is still in flight at the moment `leave` is called.
+    let set_location = room_a.update(cx_a, |room, cx| room.set_location(Some(&project_a), cx));
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+
+    set_location.await.unwrap();
+    assert_eq!(
+        room_a.read_with(cx_a, |room, _| room.location_broadcasts_sent_for_test()),
+        1
+    );
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_noise_suppression_survives_reconnect(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, _| assert!(room.noise_suppression_enabled()));
+    room_a.update(cx_a, |room, cx| room.set_noise_suppression(false, cx));
+    room_a.read_with(cx_a, |room, _| assert!(!room.noise_suppression_enabled()));
+
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| assert!(!room.noise_suppression_enabled()));
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_echo_cancellation_survives_reconnect(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, _| assert!(room.echo_cancellation_enabled()));
+    room_a.update(cx_a, |room, cx| room.set_echo_cancellation(false, cx));
+    room_a.read_with(cx_a, |room, _| assert!(!room.echo_cancellation_enabled()));
+
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| assert!(!room.echo_cancellation_enabled()));
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_gain_clamps_and_survives_reconnect(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.output_gain(), 1.0);
+        assert_eq!(room.input_gain(), 1.0);
+    });
+
+    room_a.update(cx_a, |room, cx| room.set_output_gain(1.5, cx));
+    room_a.update(cx_a, |room, cx| room.set_input_gain(-1.0, cx));
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.output_gain(), 1.5);
+        assert_eq!(room.input_gain(), 0.0, "gain should clamp to the supported range");
+    });
+
+    room_a.update(cx_a, |room, cx| room.set_output_gain(10.0, cx));
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(
+            room.output_gain(),
+            2.0,
+            "gain should clamp at the top of the supported range too"
+        );
+    });
+
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.output_gain(), 2.0);
+        assert_eq!(room.input_gain(), 0.0);
+    });
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_follow_target_resumes_after_reconnect(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let user_b_id = client_b.user_id().unwrap();
+    let peer_id_b = room_a.update(cx_a, |room, cx| {
+        room.follow(user_b_id, cx);
+        room.follow_target().unwrap()
+    });
+
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(!room.is_reconnecting());
+        assert_eq!(room.follow_target(), Some(peer_id_b));
+    });
+}
+
+#[gpui::test]
+async fn test_follow_target_timeout_is_canceled_on_leave(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    room::set_follow_target_timeout_for_test(Duration::from_millis(1));
+
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let user_b_id = client_b.user_id().unwrap();
+    room_a.update(cx_a, |room, cx| room.follow(user_b_id, cx));
+
+    // Subscribed directly to `room_a` rather than via `ActiveCall`, since hanging up drops
+    // `ActiveCall`'s relay subscription along with its room - but the (still-held) `room_a`
+    // model itself keeps running any background task that survives the leave.
+    let events_a = Rc::new(RefCell::new(Vec::new()));
+    cx_a.update(|cx| {
+        let events_a = events_a.clone();
+        cx.subscribe(&room_a, move |_, event, _| events_a.borrow_mut().push(event.clone()))
+            .detach()
+    });
+
+    // The local client drops its connection, arming the follow-target-expiry timer, but we
+    // leave before it (or a reconnect) has a chance to run.
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.run_until_parked();
+    cx_a.read(ActiveCall::global)
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .detach();
+    executor.run_until_parked();
+
+    // If the timer had survived the leave, advancing well past it would fire
+    // `FollowTargetLost` into a room nobody's in anymore.
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+
+    assert!(!events_a
+        .borrow()
+        .iter()
+        .any(|event| matches!(event, room::Event::FollowTargetLost { .. })));
+
+    room::set_follow_target_timeout_for_test(room::FOLLOW_TARGET_TIMEOUT);
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_reconnect_attempts_reset_on_success(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(!room.is_reconnecting());
+        assert_eq!(room.reconnect_attempts(), 0);
+    });
+
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(!room.is_reconnecting());
+        assert_eq!(room.reconnect_attempts(), 0);
+    });
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_leave_reports_session_summary(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    cx_b.read(ActiveCall::global)
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    cx_c.read(ActiveCall::global)
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // Two reconnects (connections are never forbidden, so each one succeeds), with all three
+    // participants present throughout.
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+
+    let summary = active_call_a
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    assert_eq!(summary.reconnect_count, 2);
+    assert_eq!(summary.peak_participant_count, 3);
+}
+
+#[gpui::test]
+async fn test_first_participant_joined_fires_once(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let events = active_call_events(cx_a);
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    cx_b.read(ActiveCall::global)
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        events
+            .borrow()
+            .iter()
+            .filter(|event| matches!(event, room::Event::FirstParticipantJoined { .. }))
+            .count(),
+        1
+    );
+    events.borrow_mut().clear();
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    cx_c.read(ActiveCall::global)
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(events
+        .borrow()
+        .iter()
+        .all(|event| !matches!(event, room::Event::FirstParticipantJoined { .. })));
+}
+
+#[gpui::test]
+async fn test_participants_where(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_b.update(cx_b, |room, cx| room.toggle_mute(cx));
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.participants_where(|p| p.muted).count(), 1);
+        assert_eq!(room.participants_where(|p| !p.muted).count(), 0);
+    });
+}
+
+#[gpui::test]
+async fn test_all_participants_including_self(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, cx| {
+        assert_eq!(room.remote_participants().len(), 1);
+        let all = room.all_participants_including_self(cx);
+        assert_eq!(all.len(), 2);
+        assert!(all.iter().any(|p| p.user.github_login == "user_a"));
+        assert!(all.iter().any(|p| p.user.github_login == "user_b"));
+    });
+}
+
+#[gpui::test]
+async fn test_participant_handle_notifies_only_that_participant(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [
+            (&client_a, cx_a),
+            (&client_b, cx_b),
+            (&client_c, cx_c),
+        ])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    cx_b.read(ActiveCall::global)
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    cx_c.read(ActiveCall::global)
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    let room_b = cx_b
+        .read(ActiveCall::global)
+        .read_with(cx_b, |call, _| call.room().unwrap().clone());
+    let peer_id_b = room_a.read_with(cx_a, |room, _| {
+        room.remote_participants()
+            .values()
+            .find(|p| p.user.github_login == "user_b")
+            .unwrap()
+            .peer_id
+    });
+    let peer_id_c = room_a.read_with(cx_a, |room, _| {
+        room.remote_participants()
+            .values()
+            .find(|p| p.user.github_login == "user_c")
+            .unwrap()
+            .peer_id
+    });
+
+    let handle_b = room_a.read_with(cx_a, |room, _| room.participant_handle(peer_id_b).unwrap());
+    let handle_c = room_a.read_with(cx_a, |room, _| room.participant_handle(peer_id_c).unwrap());
+    let notified_b = std::rc::Rc::new(std::cell::Cell::new(false));
+    let notified_c = std::rc::Rc::new(std::cell::Cell::new(false));
+    let _subscription_b = {
+        let notified_b = notified_b.clone();
+        cx_a.update(|cx| {
+            cx.observe(&handle_b, move |_, _| notified_b.set(true))
+        })
+    };
+    let _subscription_c = {
+        let notified_c = notified_c.clone();
+        cx_a.update(|cx| {
+            cx.observe(&handle_c, move |_, _| notified_c.set(true))
+        })
+    };
+
+    room_b.update(cx_b, |room, cx| room.toggle_mute(cx));
+    executor.run_until_parked();
+
+    assert!(notified_b.get());
+    assert!(!notified_c.get());
+}
+
+#[gpui::test]
+async fn test_set_location_resolves_broadcast_location(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let project_a = client_a.build_empty_local_project(cx_a);
+    let project_id = room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    let location = room_a
+        .update(cx_a, |room, cx| room.set_location(Some(&project_a), cx))
+        .await
+        .unwrap();
+    assert_eq!(location, ParticipantLocation::SharedProject { project_id });
+}
+
+#[gpui::test]
+async fn test_flush_location(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let project_a = client_a.build_empty_local_project(cx_a);
+    let project_id = room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    // Queue up another, unrelated request first so flush_location's own request has to share
+    // the connection with something already in flight, the way it would if a debounce timer's
+    // request were still pending when focus is regained.
+    let _pending = room_a.update(cx_a, |room, cx| room.ping(cx));
+
+    room_a
+        .update(cx_a, |room, cx| room.flush_location(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| {
+        let participant = room
+            .remote_participants()
+            .get(&client_a.user_id().unwrap())
+            .unwrap();
+        assert_eq!(
+            participant.location,
+            ParticipantLocation::SharedProject { project_id }
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_resolved_location(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let active_call_c = cx_c.read(ActiveCall::global);
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    let room_c = active_call_c.read_with(cx_c, |call, _| call.room().unwrap().clone());
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let project_a = client_a.build_empty_local_project(cx_a);
+    let project_id = room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // `client_b` joins the shared project, so it can resolve `client_a`'s location to an
+    // actual handle. `client_c` never does, so it only knows the wire-level project id.
+    let project_b = server.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    let a_peer_id = room_b.read_with(cx_b, |room, _| {
+        room.remote_participants()
+            .get(&client_a.user_id().unwrap())
+            .unwrap()
+            .peer_id
+    });
+
+    room_b.read_with(cx_b, |room, cx| {
+        match room.resolved_location(a_peer_id, cx).unwrap() {
+            ResolvedLocation::SharedProject(project) => {
+                assert_eq!(project, project_b);
+            }
+            other => panic!("expected a resolved shared project, got {other:?}"),
+        }
+    });
+
+    room_c.read_with(cx_c, |room, cx| {
+        assert!(matches!(
+            room.resolved_location(a_peer_id, cx),
+            Some(ResolvedLocation::UnknownProject)
+        ));
+    });
+}
+
+#[gpui::test]
+async fn test_participants_by_location_kind(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let client_d = server.create_client(cx_d, "user_d").await;
+    server
+        .make_contacts(&mut [
+            (&client_a, cx_a),
+            (&client_b, cx_b),
+            (&client_c, cx_c),
+            (&client_d, cx_d),
+        ])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_d.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    cx_b.read(ActiveCall::global)
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    cx_c.read(ActiveCall::global)
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    cx_d.read(ActiveCall::global)
+        .update(cx_d, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+
+    // `client_b` is in a shared project.
+    client_b.fs().insert_tree("/b", json!({})).await;
+    let project_b = client_b.build_empty_local_project(cx_b);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+    room_b
+        .update(cx_b, |room, cx| room.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+    room_b
+        .update(cx_b, |room, cx| room.share_project(project_b.clone(), cx))
+        .await
+        .unwrap();
+
+    // `client_c` is in a project it hasn't shared.
+    client_c.fs().insert_tree("/c", json!({})).await;
+    let project_c = client_c.build_empty_local_project(cx_c);
+    let active_call_c = cx_c.read(ActiveCall::global);
+    let room_c = active_call_c.read_with(cx_c, |call, _| call.room().unwrap().clone());
+    room_c
+        .update(cx_c, |room, cx| room.set_location(Some(&project_c), cx))
+        .await
+        .unwrap();
+
+    // `client_d` never opens a project, so it stays external.
+    executor.run_until_parked();
+
+    let (b_peer_id, c_peer_id, d_peer_id) = room_a.read_with(cx_a, |room, _| {
+        let peer_id_for =
+            |user_id: u64| room.remote_participants().get(&user_id).unwrap().peer_id;
+        (
+            peer_id_for(client_b.user_id().unwrap()),
+            peer_id_for(client_c.user_id().unwrap()),
+            peer_id_for(client_d.user_id().unwrap()),
+        )
+    });
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(
+            room.participants_by_location_kind(LocationKind::SharedProject),
+            vec![b_peer_id]
+        );
+        assert_eq!(
+            room.participants_by_location_kind(LocationKind::PrivateProject),
+            vec![c_peer_id]
+        );
+        assert_eq!(
+            room.participants_by_location_kind(LocationKind::External),
+            vec![d_peer_id]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_connection_summary(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    // There's no per-connection quality signal wired up yet, so everyone lands in `unknown`.
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(
+            room.connection_summary(),
+            ConnectionSummary {
+                good: 0,
+                fair: 0,
+                poor: 0,
+                unknown: 1,
+            }
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_room_client_accessor(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.client().user_id(), client_a.user_id());
+    });
+}
+
+#[gpui::test]
+async fn test_local_connection_lost_precedes_room_left(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let events_a = active_call_events(cx_a);
+
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+
+    let events = events_a.borrow();
+    let lost_ix = events
+        .iter()
+        .position(|event| matches!(event, room::Event::LocalConnectionLost { .. }))
+        .expect("LocalConnectionLost was not emitted");
+    let left_ix = events
+        .iter()
+        .position(|event| matches!(event, room::Event::RoomLeft { .. }))
+        .expect("RoomLeft was not emitted");
+    assert!(lost_ix < left_ix);
+}
+
+#[gpui::test]
+async fn test_local_connection_lost_includes_diagnostics(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let events_a = active_call_events(cx_a);
+
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+
+    let events = events_a.borrow();
+    let diagnostics = events
+        .iter()
+        .find_map(|event| match event {
+            room::Event::LocalConnectionLost { diagnostics } => Some(diagnostics.clone()),
+            _ => None,
+        })
+        .expect("LocalConnectionLost was not emitted");
+    assert!(!diagnostics.last_client_status.is_empty());
+    assert_eq!(diagnostics.reconnect_attempts, 0);
+}
+
+#[gpui::test]
+async fn test_decline_all_calls(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let client_d = server.create_client(cx_d, "user_d").await;
+    server
+        .make_contacts(&mut [
+            (&client_a, cx_a),
+            (&client_b, cx_b),
+            (&client_c, cx_c),
+            (&client_d, cx_d),
+        ])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let mut incoming_call_b = cx_b.read(ActiveCall::global).read_with(cx_b, |call, _| call.incoming());
+    let mut incoming_call_c = cx_c.read(ActiveCall::global).read_with(cx_c, |call, _| call.incoming());
+    let mut incoming_call_d = cx_d.read(ActiveCall::global).read_with(cx_d, |call, _| call.incoming());
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_d.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(incoming_call_b.next().await.unwrap().is_some());
+    assert!(incoming_call_c.next().await.unwrap().is_some());
+    assert!(incoming_call_d.next().await.unwrap().is_some());
+
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    assert_eq!(room_a.read_with(cx_a, |room, _| room.pending_participants().len()), 3);
+
+    room_a
+        .update(cx_a, |room, cx| room.decline_all_calls(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    assert!(room_a.read_with(cx_a, |room, _| room.pending_participants().is_empty()));
+    assert!(incoming_call_b.next().await.unwrap().is_none());
+    assert!(incoming_call_c.next().await.unwrap().is_none());
+    assert!(incoming_call_d.next().await.unwrap().is_none());
+}
+
+#[gpui::test]
+async fn test_respond_to_calls(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let mut incoming_call_b = cx_b.read(ActiveCall::global).read_with(cx_b, |call, _| call.incoming());
+    let mut incoming_call_c = cx_c.read(ActiveCall::global).read_with(cx_c, |call, _| call.incoming());
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(incoming_call_b.next().await.unwrap().is_some());
+    assert!(incoming_call_c.next().await.unwrap().is_some());
+
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    assert_eq!(room_a.read_with(cx_a, |room, _| room.pending_participants().len()), 2);
+
+    let mut decisions = HashMap::default();
+    decisions.insert(client_b.user_id().unwrap(), CallDecision::Accept);
+    decisions.insert(client_c.user_id().unwrap(), CallDecision::Decline);
+    let summary = room_a
+        .update(cx_a, |room, cx| room.respond_to_calls(decisions, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    assert_eq!(
+        summary,
+        CallResponseSummary {
+            accepted: 1,
+            declined: 1,
+            failed: 0,
+        }
+    );
+    // `client_b`'s invite was left outstanding; `client_c`'s was canceled.
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.pending_participants().len(), 1);
+        assert_eq!(room.pending_participants()[0].id, client_b.user_id().unwrap());
+    });
+    assert!(incoming_call_c.next().await.unwrap().is_none());
+}
+
+#[gpui::test]
+async fn test_room_ping(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let rtt = room_a
+        .update(cx_a, |room, cx| room.ping(cx))
+        .await
+        .unwrap();
+    assert!(rtt >= std::time::Duration::ZERO);
+}
+
+#[gpui::test]
+async fn test_room_await_status(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a
+        .update(cx_a, |room, cx| room.await_status(room::RoomStatus::Online, cx))
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+
+    room_a
+        .update(cx_a, |room, cx| room.await_status(room::RoomStatus::Offline, cx))
+        .await;
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.status().is_offline());
+    });
+}
+
+#[gpui::test]
+async fn test_disconnect_grace_period(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    // A disconnect shorter than `RECONNECT_TIMEOUT` keeps the roster around while the room
+    // sits in `Rejoining`, then goes back to normal once the connection comes back.
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.status().is_rejoining());
+        assert_eq!(
+            room_participants(&room_a, cx_a),
+            RoomParticipants {
+                remote: vec!["user_b".to_string()],
+                pending: vec![]
+            }
+        );
+    });
+
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+    room_a.read_with(cx_a, |room, _| assert!(room.status().is_online()));
+    assert_eq!(
+        room_participants(&room_a, cx_a),
+        RoomParticipants {
+            remote: vec!["user_b".to_string()],
+            pending: vec![]
+        }
+    );
+
+    // A disconnect that outlasts the grace period gives up and clears the roster.
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.status().is_offline());
+        assert_eq!(
+            room_participants(&room_a, cx_a),
+            RoomParticipants {
+                remote: vec![],
+                pending: vec![]
+            }
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_persist_and_restore_session(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.update(cx_a, |room, cx| room.toggle_mute(cx));
+    let session = room_a.read_with(cx_a, |room, _| room.persist_session());
+    assert!(session.muted);
+
+    // Simulate an app restart: a fresh `Room` is rebuilt from nothing but the persisted
+    // session state (rather than `room_a`, whose in-memory state wouldn't have survived).
+    let restored_room = Room::restore_session(
+        session,
+        client_a.client().clone(),
+        client_a.user_store().clone(),
+        cx_a.to_async(),
+    )
+    .await
+    .unwrap();
+
+    restored_room.read_with(cx_a, |room, _| {
+        assert_eq!(room.id(), session.room_id);
+        assert!(room.is_muted());
+    });
+}
+
+#[gpui::test]
+async fn test_leave_with_farewell_message(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, _room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let events_b = active_call_events(cx_b);
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.hang_up_with_message(Some("be right back".to_string()), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    assert!(events_b.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::ParticipantFarewell { message, reason, .. }
+            if message.as_deref() == Some("be right back")
+                && *reason == proto::LeaveReason::Intentional
+    )));
+}
+
+#[gpui::test]
+async fn test_participant_left_kick_reason(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let events_a = active_call_events(cx_a);
+    room_a.update(cx_a, |room, cx| {
+        room.simulate_participant_left_for_test(
+            client_b.peer_id().unwrap(),
+            None,
+            proto::LeaveReason::Kicked,
+            cx,
+        )
+    });
+
+    assert!(events_a.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::ParticipantFarewell { reason, .. } if *reason == proto::LeaveReason::Kicked
+    )));
+}
+
+#[gpui::test]
+async fn test_resync_after_apply_error(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.remote_participants().len(), 1);
+    });
+
+    // Feed the room an update for a different room id. It should be rejected outright, leaving
+    // the existing roster untouched rather than clobbering it.
+    let bogus_room_id = room_b.read_with(cx_b, |room, _| room.id()) + 1;
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: bogus_room_id,
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap_err();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.remote_participants().len(), 1);
+    });
+
+    // Resyncing re-fetches a fresh snapshot from the server and reapplies it, which is what
+    // `handle_room_updated` does automatically after a failed apply.
+    room_b
+        .update(cx_b, |room, cx| room.resync(cx))
+        .await
+        .unwrap();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.remote_participants().len(), 1);
+    });
+}
+
+#[gpui::test]
+async fn test_suspicious_mass_removal_triggers_resync(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.remote_participants().len(), 1);
+        assert_eq!(room.audit_log().len(), 1);
+    });
+
+    // A diff that drops the entire roster at once looks like a corrupted delta rather than
+    // everyone actually leaving simultaneously, so it should trigger a resync instead of being
+    // applied directly.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+    executor.run_until_parked();
+
+    // The resync restores the server's real roster (user_a never actually left). If the
+    // suspicious diff had instead been applied directly, the audit log would show a spurious
+    // `Left` entry for user_a (and a second `Joined` once the resync brought them back).
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.remote_participants().len(), 1);
+        assert_eq!(room.audit_log().len(), 1);
+        assert_eq!(room.audit_log()[0].kind, call::room::AuditEventKind::Joined);
+    });
+}
+
+#[gpui::test]
+async fn test_mic_test_loopback_produces_levels_and_stops(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let mut handle = room_a
+        .update(cx_a, |room, cx| room.mic_test(cx))
+        .await
+        .unwrap();
+    let mut levels = handle.levels();
+
+    executor.advance_clock(std::time::Duration::from_millis(100));
+    executor.run_until_parked();
+    levels
+        .next()
+        .await
+        .expect("loopback should have produced a level sample");
+
+    // Stopping ends the loopback cleanly - no further samples are produced once parked again.
+    handle.stop();
+    executor.run_until_parked();
+}
+
+#[gpui::test]
+async fn test_pending_participants_only_update(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.pending_participants().is_empty());
+    });
+
+    // A server delta that only announces a new incoming call shouldn't need to resend the
+    // whole room.
+    room_a
+        .update(cx_a, |room, cx| {
+            room.apply_pending_participants_update_for_test(
+                vec![proto::PendingParticipant {
+                    user_id: client_c.user_id().unwrap(),
+                    calling_user_id: client_a.user_id().unwrap(),
+                    initial_project_id: None,
+                }],
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.pending_participants().len(), 1);
+        assert_eq!(room.pending_participants()[0].id, client_c.user_id().unwrap());
+        assert!(room.contains_participant(client_c.user_id().unwrap()));
+    });
+
+    // A later delta that drops `client_c` from the pending list (their invite was declined or
+    // canceled) must also drop them from `contains_participant` - not just `pending_participants`.
+    room_a
+        .update(cx_a, |room, cx| {
+            room.apply_pending_participants_update_for_test(Vec::new(), cx)
+        })
+        .await
+        .unwrap();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.pending_participants().is_empty());
+        assert!(!room.contains_participant(client_c.user_id().unwrap()));
+    });
+}
+
+#[gpui::test]
+async fn test_pending_participant_auto_expires(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+
+    room_a
+        .update(cx_a, |room, cx| {
+            room.apply_pending_participants_update_for_test(
+                vec![proto::PendingParticipant {
+                    user_id: client_c.user_id().unwrap(),
+                    calling_user_id: client_a.user_id().unwrap(),
+                    initial_project_id: None,
+                }],
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.pending_participants().len(), 1);
+    });
+
+    // An invite that goes unanswered for longer than the timeout is canceled locally, even
+    // though the server never told us to drop it.
+    executor.advance_clock(room::PENDING_PARTICIPANT_TIMEOUT);
+    executor.run_until_parked();
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.pending_participants().is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_prune_stale_participants(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.remote_participants().len(), 1);
+    });
+
+    // Simulate the server having stopped including user_a in updates for a while: advance the
+    // epoch far past user_a's last_seen without refreshing it.
+    room_b.update(cx_b, |room, _| room.set_update_epoch_for_test(10));
+
+    room_b.update(cx_b, |room, cx| room.prune_stale_participants(20, cx));
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.remote_participants().len(), 1);
+    });
+
+    room_b.update(cx_b, |room, cx| room.prune_stale_participants(1, cx));
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.remote_participants().is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_on_offline_runs_once_per_offline_transition(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let offline_count = Rc::new(Cell::new(0));
+    room_a.update(cx_a, |room, _| {
+        let offline_count = offline_count.clone();
+        room.on_offline(move |_cx| offline_count.set(offline_count.get() + 1));
+    });
+    assert_eq!(offline_count.get(), 0);
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    assert_eq!(offline_count.get(), 1);
+
+    // A second, redundant hang up (we're already offline) shouldn't run the callback again.
+    active_call_a
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    assert_eq!(offline_count.get(), 1);
+}
+
+#[gpui::test]
+async fn test_refresh_presence_marks_stale_participants_away(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let peer_id_a = client_a.peer_id().unwrap();
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.away_participants().is_empty());
+    });
+
+    // Simulate the server having stopped including user_a in updates for a while: advance the
+    // epoch far past user_a's last_seen without refreshing it.
+    room_b.update(cx_b, |room, _| room.set_update_epoch_for_test(10));
+
+    room_b.update(cx_b, |room, cx| room.refresh_presence(20, cx));
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.away_participants().is_empty());
+    });
+
+    room_b.update(cx_b, |room, cx| room.refresh_presence(1, cx));
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.away_participants(), vec![peer_id_a]);
+    });
+}
+
+#[gpui::test]
+async fn test_participants_by_recent_speech_sorts_by_recency_then_join_order(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    cx_b.read(ActiveCall::global)
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    cx_c.read(ActiveCall::global)
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    let peer_id_b = client_b.peer_id().unwrap();
+    let peer_id_c = client_c.peer_id().unwrap();
+
+    // Neither remote participant has spoken yet, so they fall back to join order.
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.participants_by_recent_speech(), vec![peer_id_b, peer_id_c]);
+    });
+
+    // The participant who joined second, but spoke most recently, sorts first.
+    room_a.update(cx_a, |room, _| {
+        room.mark_speaking_for_test(client_c.user_id().unwrap())
+    });
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.participants_by_recent_speech(), vec![peer_id_c, peer_id_b]);
+    });
+
+    // A later speaker displaces an earlier one.
+    room_a.update(cx_a, |room, _| {
+        room.mark_speaking_for_test(client_b.user_id().unwrap())
+    });
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.participants_by_recent_speech(), vec![peer_id_b, peer_id_c]);
+    });
+}
+
+#[gpui::test]
+async fn test_outgoing_calls(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server.make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)]).await;
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.outgoing_calls().is_empty());
+    });
+
+    let call = room_a.update(cx_a, |room, cx| {
+        room.call_for_test(client_c.user_id().unwrap(), cx)
+    });
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.outgoing_calls(), &[client_c.user_id().unwrap()]);
+    });
+
+    call.await.unwrap();
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.outgoing_calls().is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_video_enabled_toggle(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let events = active_call_events(cx_a);
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(!room.is_video_enabled());
+    });
+
+    room_a.update(cx_a, |room, cx| room.enable_video(cx));
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.is_video_enabled());
+    });
+    assert!(events
+        .borrow()
+        .iter()
+        .any(|event| matches!(event, room::Event::VideoChanged)));
+    events.borrow_mut().clear();
+
+    room_a.update(cx_a, |room, cx| room.disable_video(cx));
+    room_a.read_with(cx_a, |room, _| {
+        assert!(!room.is_video_enabled());
+    });
+    assert!(events
+        .borrow()
+        .iter()
+        .any(|event| matches!(event, room::Event::VideoChanged)));
+}
+
+#[gpui::test]
+async fn test_video_participants(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.video_participants().is_empty());
+    });
+
+    // User A shares their screen; user C never does.
+    let display = MacOSDisplay::new();
+    room_a
+        .update(cx_a, |room, cx| {
+            room.set_display_sources(vec![display.clone()]);
+            room.share_screen(cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.video_participants(),
+            &[client_a.peer_id().unwrap()]
+        );
+    });
+
+    room_b.update(cx_b, |room, cx| {
+        assert_eq!(room.requested_video_quality(client_a.peer_id().unwrap()), None);
+        room.request_video_quality(client_a.peer_id().unwrap(), VideoQuality::Low, cx)
+    });
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.requested_video_quality(client_a.peer_id().unwrap()),
+            Some(VideoQuality::Low)
+        );
+    });
+
+    // User C never shared video, so requesting a quality for them is rejected.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.request_video_quality(client_c.peer_id().unwrap(), VideoQuality::Low, cx)
+        })
+        .await
+        .unwrap_err();
+}
+
+#[gpui::test]
+async fn test_set_location_project_removal_race(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let project_a = client_a.build_empty_local_project(cx_a);
+    let project_id = room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    // The project gets unpublished from under us while the broadcast is still in flight.
+    let set_location = room_a.update(cx_a, |room, cx| room.set_location(Some(&project_a), cx));
+    room_a.update(cx_a, |room, _| {
+        room.drop_shared_project_for_test(project_id)
+    });
+
+    let location = set_location.await.unwrap();
+    assert_eq!(location, ParticipantLocation::External);
+}
+
+#[gpui::test]
+async fn test_join_timeout(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // A zero-length timeout should always lose the race against the real join round trip.
+    room::set_join_timeout_for_test(Duration::ZERO);
+    let result = active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await;
+    room::set_join_timeout_for_test(Duration::from_secs(15));
+
+    assert!(result.is_err());
+    active_call_b.read_with(cx_b, |call, _| assert!(call.room().is_none()));
+}
+
+#[gpui::test]
+async fn test_room_capacity_events(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+    let events_a = active_call_events(cx_a);
+
+    room_a.update(cx_a, |room, cx| room.set_max_participants(Some(3), cx));
+    assert!(events_a.borrow().is_empty());
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    assert_eq!(events_a.borrow().len(), 1);
+    assert!(matches!(
+        events_a.borrow().first().unwrap(),
+        room::Event::RoomFull
+    ));
+    events_a.borrow_mut().clear();
+
+    active_call_c
+        .update(cx_c, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    assert_eq!(events_a.borrow().len(), 1);
+    assert!(matches!(
+        events_a.borrow().first().unwrap(),
+        room::Event::RoomHasCapacity
+    ));
+}
+
+#[gpui::test]
+async fn test_queued_call_dispatched_when_slot_frees_up(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+
+    // The room is already full with `client_a` and `client_b`, so queuing a call to `client_c`
+    // shouldn't dispatch it yet.
+    room_a.update(cx_a, |room, cx| room.set_max_participants(Some(2), cx));
+    room_a
+        .update(cx_a, |room, cx| {
+            room.queue_call(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.queued_calls(), &[client_c.user_id().unwrap()]);
+    });
+    let active_call_c = cx_c.read(ActiveCall::global);
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap_err();
+
+    // Once `client_b` leaves, freeing a slot, the queued call to `client_c` is dispatched
+    // automatically.
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_b
+        .update(cx_b, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.queued_calls().is_empty());
+    });
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+}
+
+#[gpui::test]
+async fn test_multiple_queued_calls_dispatched_when_slot_frees_up(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let client_d = server.create_client(cx_d, "user_d").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c), (&client_d, cx_d)])
+        .await;
+
+    // The room is already full with `client_a` and `client_b`, so queuing calls to `client_c`
+    // and `client_d` shouldn't dispatch either of them yet.
+    room_a.update(cx_a, |room, cx| room.set_max_participants(Some(2), cx));
+    room_a
+        .update(cx_a, |room, cx| {
+            room.queue_call(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    room_a
+        .update(cx_a, |room, cx| {
+            room.queue_call(client_d.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(
+            room.queued_calls(),
+            &[client_c.user_id().unwrap(), client_d.user_id().unwrap()]
+        );
+    });
+
+    // Once `client_b` leaves, both queued calls are dispatched right away, not just the first
+    // one - neither invite occupies a slot until accepted, so the freed slot doesn't get "used
+    // up" by the first dispatch.
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_b
+        .update(cx_b, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.queued_calls().is_empty());
+    });
+    let active_call_c = cx_c.read(ActiveCall::global);
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    let active_call_d = cx_d.read(ActiveCall::global);
+    active_call_d
+        .update(cx_d, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+}
+
+#[gpui::test]
+async fn test_participant_parse_error_metrics(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.metrics().participant_parse_errors, 0);
+    });
+
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![proto::Participant {
+                        user_id: client_a.user_id().unwrap(),
+                        peer_id: client_a.peer_id(),
+                        projects: Vec::new(),
+                        location: None,
+                        participant_index: 0,
+                        role: proto::ChannelRole::Member as i32,
+                        platform: None,
+                        is_observer: false,
+                        mic_state: None,
+                        region: None,
+                        network_type: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.metrics().participant_parse_errors, 1);
+    });
+}
+
+#[gpui::test]
+async fn test_participant_with_zero_peer_id_is_skipped(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    room_b.update(cx_b, |room, _| {
+        room.set_mass_removal_resync_threshold(1.0);
+    });
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![proto::Participant {
+                        user_id: client_a.user_id().unwrap(),
+                        peer_id: Some(proto::PeerId::default()),
+                        projects: Vec::new(),
+                        location: None,
+                        participant_index: 0,
+                        role: proto::ChannelRole::Member as i32,
+                        platform: None,
+                        is_observer: false,
+                        mic_state: None,
+                        region: None,
+                        network_type: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.remote_participants().is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_followers_updates_from_relayed_proto(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let events_b = active_call_events(cx_b);
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    let local_peer_id = room_b.read_with(cx_b, |room, _| room.client().peer_id().unwrap());
+    let client_a_peer_id = client_a.peer_id().unwrap();
+    let participant = proto::Participant {
+        user_id: client_a.user_id().unwrap(),
+        peer_id: Some(client_a_peer_id),
+        projects: Vec::new(),
+        location: None,
+        participant_index: 0,
+        role: proto::ChannelRole::Member as i32,
+        platform: None,
+        is_observer: false,
+        mic_state: None,
+        region: None,
+        network_type: None,
+    };
+
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.followers().is_empty());
+    });
+
+    // The server relays that `client_a` is now following us.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![participant.clone()],
+                    followers: vec![proto::Follower {
+                        leader_id: Some(local_peer_id),
+                        follower_id: Some(client_a_peer_id),
+                        project_id: 0,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.followers(), vec![client_a_peer_id]);
+    });
+    assert!(events_b.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::FollowerAdded { follower_id } if *follower_id == client_a_peer_id
+    )));
+    events_b.borrow_mut().clear();
+
+    // And then stops following us.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![participant],
+                    followers: Vec::new(),
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.followers().is_empty());
+    });
+    assert!(events_b.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::FollowerRemoved { follower_id } if *follower_id == client_a_peer_id
+    )));
+}
+
+#[gpui::test]
+async fn test_project_occupancy_updates_from_relayed_proto(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let events_b = active_call_events(cx_b);
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    let client_a_peer_id = client_a.peer_id().unwrap();
+    let project_id = 101;
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.project_occupancy(project_id), 0);
+    });
+
+    // The server relays that `client_a` has entered `project_id`.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![proto::Participant {
+                        user_id: client_a.user_id().unwrap(),
+                        peer_id: Some(client_a_peer_id),
+                        projects: Vec::new(),
+                        location: Some(proto::ParticipantLocation {
+                            variant: Some(proto::participant_location::Variant::SharedProject(
+                                proto::participant_location::SharedProject {
+                                    id: project_id,
+                                    open_path: None,
+                                    anchor: None,
+                                },
+                            )),
+                        }),
+                        participant_index: 0,
+                        role: proto::ChannelRole::Member as i32,
+                        platform: None,
+                        is_observer: false,
+                        mic_state: None,
+                        region: None,
+                        network_type: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.project_occupancy(project_id), 1);
+    });
+    assert!(events_b.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::ProjectOccupancyChanged { project_id: id, count }
+            if *id == project_id && *count == 1
+    )));
+    events_b.borrow_mut().clear();
+
+    // And then leaves it again.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![proto::Participant {
+                        user_id: client_a.user_id().unwrap(),
+                        peer_id: Some(client_a_peer_id),
+                        projects: Vec::new(),
+                        location: None,
+                        participant_index: 0,
+                        role: proto::ChannelRole::Member as i32,
+                        platform: None,
+                        is_observer: false,
+                        mic_state: None,
+                        region: None,
+                        network_type: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.project_occupancy(project_id), 0);
+    });
+    assert!(events_b.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::ProjectOccupancyChanged { project_id: id, count: 0 } if *id == project_id
+    )));
+}
+
+#[gpui::test]
+async fn test_last_known_participants_after_disconnect(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.remote_participants().len(), 1);
+        assert!(room.last_known_participants().is_empty());
+    });
+
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.remote_participants().len(), 0);
+        assert_eq!(room.last_known_participants().len(), 1);
+    });
+}
+
+#[gpui::test]
+async fn test_observe_peer_projects(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let mut projects = room_b.update(cx_b, |room, cx| {
+        room.observe_peer_projects(client_a.peer_id().unwrap(), cx)
+    });
+    assert_eq!(projects.next().await.unwrap(), Vec::<u64>::new());
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let project_a = client_a.build_empty_local_project(cx_a);
+    let project_id = room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(projects.next().await.unwrap(), vec![project_id]);
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(projects.next().await.is_none());
+}
+
+#[gpui::test]
+async fn test_observe_mute(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let mut muted = room_b.update(cx_b, |room, cx| {
+        room.observe_mute(client_a.peer_id().unwrap(), cx)
+    });
+    assert_eq!(muted.next().await.unwrap(), false);
+
+    room_a.update(cx_a, |room, cx| room.toggle_mute(cx));
+    executor.run_until_parked();
+    assert_eq!(muted.next().await.unwrap(), true);
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(muted.next().await.is_none());
+}
+
+#[gpui::test]
+async fn test_find_participants(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_carol").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(
+            room.find_participants("user_b"),
+            vec![client_b.peer_id().unwrap()]
+        );
+        assert_eq!(
+            room.find_participants("CAROL"),
+            vec![client_c.peer_id().unwrap()]
+        );
+        assert_eq!(
+            room.find_participants("user_"),
+            vec![client_b.peer_id().unwrap(), client_c.peer_id().unwrap()],
+            "results should come back in a stable order, not reshuffled by match quality"
+        );
+        assert!(room.find_participants("nonexistent").is_empty());
+        assert_eq!(
+            room.find_participants(&client_b.user_id().unwrap().to_string()),
+            vec![client_b.peer_id().unwrap()]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_media_token(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.media_token().is_none());
+    });
+
+    let events_a = active_call_events(cx_a);
+    room_a.update(cx_a, |room, cx| {
+        room.refresh_media_token_for_test("new-token".to_string(), cx)
+    });
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.media_token(), Some("new-token"));
+    });
+    assert!(events_a
+        .borrow()
+        .iter()
+        .any(|event| *event == room::Event::MediaTokenRefreshed));
+}
+
+#[gpui::test]
+async fn test_call_deduplication(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+
+    let first = room_a.update(cx_a, |room, cx| {
+        room.call_for_test(client_c.user_id().unwrap(), cx)
+    });
+    let second = room_a.update(cx_a, |room, cx| {
+        room.call_for_test(client_c.user_id().unwrap(), cx)
+    });
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.outgoing_calls(), &[client_c.user_id().unwrap()]);
+    });
+
+    first.await.unwrap();
+    second.await.unwrap();
+}
+
+#[gpui::test]
+async fn test_guarded_methods_reject_reconnecting(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    room_a.update(cx_a, |room, cx| {
+        room.set_status_for_test(room::RoomStatus::Rejoining, cx)
+    });
+
+    room_a
+        .update(cx_a, |room, cx| room.ping(cx))
+        .await
+        .unwrap_err();
+    room_a
+        .update(cx_a, |room, cx| room.share_microphone(cx))
+        .await
+        .unwrap_err();
+    room_a
+        .update(cx_a, |room, cx| room.share_screen(cx))
+        .await
+        .unwrap_err();
+    room_a
+        .update(cx_a, |room, cx| room.set_location(None, cx))
+        .await
+        .unwrap_err();
+    room_a
+        .update(cx_a, |room, cx| room.call_for_test(1, cx))
+        .await
+        .unwrap_err();
+    room_a
+        .update(cx_a, |room, cx| room.unshare_screen(cx))
+        .unwrap_err();
+}
+
+#[gpui::test]
+async fn test_screen_share_track_accessor(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let peer_id_a = client_a.peer_id().unwrap();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.screen_share_track(peer_id_a).is_none());
+    });
+
+    let display = MacOSDisplay::new();
+    room_a
+        .update(cx_a, |room, cx| {
+            room.set_display_sources(vec![display]);
+            room.share_screen(cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.screen_share_track(peer_id_a).is_some());
+    });
+
+    room_a
+        .update(cx_a, |room, cx| room.unshare_screen(cx))
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.screen_share_track(peer_id_a).is_none());
+    });
+}
+
+#[gpui::test]
+async fn test_role_changed_event(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let events_b = active_call_events(cx_b);
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![proto::Participant {
+                        user_id: client_a.user_id().unwrap(),
+                        peer_id: client_a.peer_id(),
+                        projects: Vec::new(),
+                        location: Some(proto::ParticipantLocation {
+                            variant: Some(proto::participant_location::Variant::External(
+                                proto::participant_location::External {},
+                            )),
+                        }),
+                        participant_index: 0,
+                        role: proto::ChannelRole::Admin as i32,
+                        platform: None,
+                        is_observer: false,
+                        mic_state: None,
+                        region: None,
+                        network_type: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    assert!(events_b.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::RoleChanged { role, .. } if *role == proto::ChannelRole::Admin
+    )));
+}
+
+#[gpui::test]
+async fn test_project_access_reports_read_only(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let project_id = 101;
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.project_access(project_id), None);
+    });
+
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![proto::Participant {
+                        user_id: client_a.user_id().unwrap(),
+                        peer_id: client_a.peer_id(),
+                        projects: vec![proto::ParticipantProject {
+                            id: project_id,
+                            worktree_root_names: vec!["project".to_string()],
+                            read_only: true,
+                        }],
+                        location: Some(proto::ParticipantLocation {
+                            variant: Some(proto::participant_location::Variant::SharedProject(
+                                proto::participant_location::SharedProject {
+                                    id: project_id,
+                                    open_path: None,
+                                    anchor: None,
+                                },
+                            )),
+                        }),
+                        participant_index: 0,
+                        role: proto::ChannelRole::Member as i32,
+                        platform: None,
+                        is_observer: false,
+                        mic_state: None,
+                        region: None,
+                        network_type: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.project_access(project_id),
+            Some(call::participant::ProjectAccess::ReadOnly)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_set_project_access_requires_host(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) = join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a.fs().insert_tree("/a", json!({ "a.txt": "" })).await;
+    let (project_a, _worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // The host can flip the project's access level.
+    room_a
+        .update(cx_a, |room, cx| {
+            room.set_project_access(project_id, call::participant::ProjectAccess::ReadOnly, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(
+            room.project_access(project_id),
+            Some(call::participant::ProjectAccess::ReadOnly)
+        );
+    });
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.project_access(project_id),
+            Some(call::participant::ProjectAccess::ReadOnly)
+        );
+    });
+
+    // A participant who doesn't own the project can't change its access level.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.set_project_access(project_id, call::participant::ProjectAccess::ReadWrite, cx)
+        })
+        .await
+        .unwrap_err();
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(
+            room.project_access(project_id),
+            Some(call::participant::ProjectAccess::ReadOnly)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_set_location_auto_publish(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a.fs().insert_tree("/a", json!({ "1.txt": "" })).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+
+    // The project hasn't been shared yet - `set_location_auto_publish` should publish it and
+    // broadcast the resulting `SharedProject` location in one go.
+    assert!(project_a.read_with(cx_a, |project, _| project.remote_id()).is_none());
+    let location = room_a
+        .update(cx_a, |room, cx| {
+            room.set_location_auto_publish(&project_a, cx)
+        })
+        .await
+        .unwrap();
+    let project_id = project_a
+        .read_with(cx_a, |project, _| project.remote_id())
+        .expect("project should have been published");
+    assert_eq!(location, ParticipantLocation::SharedProject { project_id });
+
+    executor.run_until_parked();
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.remote_participants()[&client_a.user_id().unwrap()].location,
+            ParticipantLocation::SharedProject { project_id }
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_quick_rejoin_restores_cached_location(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    let project_id = 55;
+
+    let shared_project_participant = || proto::Participant {
+        user_id: client_a.user_id().unwrap(),
+        peer_id: client_a.peer_id(),
+        projects: vec![proto::ParticipantProject {
+            id: project_id,
+            worktree_root_names: vec!["project".to_string()],
+            read_only: false,
+        }],
+        location: Some(proto::ParticipantLocation {
+            variant: Some(proto::participant_location::Variant::SharedProject(
+                proto::participant_location::SharedProject {
+                    id: project_id,
+                    open_path: None,
+                    anchor: None,
+                },
+            )),
+        }),
+        participant_index: 0,
+        role: proto::ChannelRole::Member as i32,
+        platform: None,
+        is_observer: false,
+        mic_state: None,
+        region: None,
+        network_type: None,
+    };
+
+    // User A is sharing a project.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.set_mass_removal_resync_threshold(1.0);
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![shared_project_participant()],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.remote_participants()[&client_a.user_id().unwrap()].location,
+            ParticipantLocation::SharedProject { project_id }
+        );
+    });
+
+    // User A briefly drops out of the roster entirely (e.g. a quick reconnect).
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: Vec::new(),
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.remote_participants().is_empty());
+    });
+
+    // They rejoin with a new peer id before reporting any location of their own yet - the
+    // cached location should be restored rather than showing them as nowhere.
+    let mut rejoined = shared_project_participant();
+    rejoined.peer_id = Some(proto::PeerId {
+        id: client_a.peer_id().unwrap().id + 1000,
+        owner_id: client_a.peer_id().unwrap().owner_id,
+    });
+    rejoined.location = None;
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![rejoined],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.remote_participants()[&client_a.user_id().unwrap()].location,
+            ParticipantLocation::SharedProject { project_id }
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_request_mute_does_not_auto_mute(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    room_a.read_with(cx_a, |room, _| assert!(room.can_moderate()));
+
+    let events_b = active_call_events(cx_b);
+
+    // The host merely asks user B to mute - B stays unmuted until they act on it themselves.
+    room_a
+        .update(cx_a, |room, cx| {
+            room.request_mute(client_b.user_id().unwrap(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_b.read_with(cx_b, |room, _| assert!(!room.is_muted()));
+    assert!(events_b
+        .borrow()
+        .iter()
+        .any(|event| matches!(event, room::Event::MuteRequested { .. })));
+}
+
+#[gpui::test]
+async fn test_counts_distinguishes_joined_from_pending(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(
+            room.counts(),
+            call::RoomCounts {
+                joined: 1,
+                pending: 1,
+                local: 1,
+            }
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_on_event_filters_to_matching_variant(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, _room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let mute_events = Rc::new(RefCell::new(Vec::new()));
+    cx_a.update(|cx| {
+        let mute_events = mute_events.clone();
+        Room::on_event(&room_a, room::EventKind::LocalMuteChanged, cx, move |_, event, _| {
+            mute_events.borrow_mut().push(event.clone());
+        })
+        .detach();
+    });
+
+    // Toggling mute fires `RemoteAudioTracksChanged` for the listening peer and `VideoChanged`
+    // isn't involved at all, but the filter should only ever let `LocalMuteChanged` through.
+    room_a.update(cx_a, |room, cx| room.toggle_mute(cx));
+    executor.run_until_parked();
+
+    let events = mute_events.borrow();
+    assert_eq!(events.len(), 1);
+    assert!(matches!(
+        events[0],
+        room::Event::LocalMuteChanged { muted: true }
+    ));
+}
+
+#[gpui::test]
+async fn test_single_update_emits_one_event_per_changed_field(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let events_b = active_call_events(cx_b);
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    // A single roster update that changes both location and observer mode for the same
+    // participant at once should emit an event for each field, not just one coarse notify.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![proto::Participant {
+                        user_id: client_a.user_id().unwrap(),
+                        peer_id: client_a.peer_id(),
+                        projects: Vec::new(),
+                        location: Some(proto::ParticipantLocation {
+                            variant: Some(proto::participant_location::Variant::UnsharedProject(
+                                proto::participant_location::UnsharedProject {},
+                            )),
+                        }),
+                        participant_index: 0,
+                        role: proto::ChannelRole::Member as i32,
+                        platform: None,
+                        is_observer: true,
+                        mic_state: None,
+                        region: None,
+                        network_type: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    assert!(events_b
+        .borrow()
+        .iter()
+        .any(|event| matches!(event, room::Event::ParticipantLocationChanged { .. })));
+    assert!(events_b.borrow().iter().any(|event| matches!(
+        event,
+        room::Event::ParticipantObserverModeChanged { is_observer, .. } if *is_observer
+    )));
+}
+
+#[gpui::test]
+async fn test_cohost_can_moderate(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_c.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    cx_a.executor().run_until_parked();
+    let active_call_c = cx_c.read(ActiveCall::global);
+    active_call_c
+        .update(cx_c, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    cx_a.executor().run_until_parked();
+
+    // user_a is the host; user_b starts out as a regular member.
+    room_a
+        .update(cx_a, |room, cx| {
+            room.promote_to_cohost(client_b.user_id().unwrap(), cx)
+        })
+        .await
+        .unwrap();
+    cx_a.executor().run_until_parked();
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.local_participant().role, proto::ChannelRole::CoHost);
+    });
+
+    // The co-host, not just the host, can remove another participant.
+    room_b
+        .update(cx_b, |room, cx| {
+            room.remove_participant(client_c.user_id().unwrap(), cx)
+        })
+        .await
+        .unwrap();
+    cx_a.executor().run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room
+            .remote_participants()
+            .values()
+            .all(|participant| participant.user.id != client_c.user_id().unwrap()));
+    });
+}
+
+#[gpui::test]
+async fn test_guest_cannot_moderate(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
             call.invite(client_c.user_id().unwrap(), None, cx)
         })
         .await
         .unwrap();
-    executor.run_until_parked();
+    cx_a.executor().run_until_parked();
+    let active_call_c = cx_c.read(ActiveCall::global);
     active_call_c
         .update(cx_c, |call, cx| call.accept_incoming(cx))
         .await
         .unwrap();
-    executor.run_until_parked();
+    cx_a.executor().run_until_parked();
 
-    // User A does not hear users B or C.
-    assert_eq!(
-        participant_audio_state(&room_a, cx_a),
-        &[
-            ParticipantAudioState {
-                user_id: client_b.user_id().unwrap(),
-                is_muted: false,
-                audio_tracks_playing: vec![false],
-            },
-            ParticipantAudioState {
-                user_id: client_c.user_id().unwrap(),
-                is_muted: false,
-                audio_tracks_playing: vec![false],
-            }
-        ]
-    );
-    assert_eq!(
-        participant_audio_state(&room_b, cx_b),
-        &[
-            ParticipantAudioState {
-                user_id: client_a.user_id().unwrap(),
-                is_muted: true,
-                audio_tracks_playing: vec![true],
-            },
-            ParticipantAudioState {
-                user_id: client_c.user_id().unwrap(),
-                is_muted: false,
-                audio_tracks_playing: vec![true],
-            }
-        ]
-    );
+    room_a
+        .update(cx_a, |room, cx| {
+            room.set_participant_role(client_b.user_id().unwrap(), proto::ChannelRole::Guest, cx)
+        })
+        .await
+        .unwrap();
+    cx_a.executor().run_until_parked();
 
-    #[derive(PartialEq, Eq, Debug)]
-    struct ParticipantAudioState {
-        user_id: u64,
-        is_muted: bool,
-        audio_tracks_playing: Vec<bool>,
-    }
+    let result = room_b
+        .update(cx_b, |room, cx| {
+            room.remove_participant(client_c.user_id().unwrap(), cx)
+        })
+        .await;
+    assert!(result.is_err());
+}
 
-    fn participant_audio_state(
-        room: &Model<Room>,
-        cx: &TestAppContext,
-    ) -> Vec<ParticipantAudioState> {
-        room.read_with(cx, |room, _| {
-            room.remote_participants()
-                .iter()
-                .map(|(user_id, participant)| ParticipantAudioState {
-                    user_id: *user_id,
-                    is_muted: participant.muted,
-                    audio_tracks_playing: participant
-                        .audio_tracks
-                        .values()
-                        .map(|track| track.is_playing())
-                        .collect(),
-                })
-                .collect::<Vec<_>>()
+#[gpui::test]
+async fn test_call_with_context(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let mut incoming_call_b = active_call_b.read_with(cx_b, |call, _| call.incoming());
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite_with_context(
+                client_b.user_id().unwrap(),
+                None,
+                Some("wants to pair on the auth bug".to_string()),
+                cx,
+            )
         })
-    }
+        .await
+        .unwrap();
+
+    let call_b = incoming_call_b.next().await.unwrap().unwrap();
+    assert_eq!(
+        call_b.context.as_deref(),
+        Some("wants to pair on the auth bug")
+    );
 }
 
 #[gpui::test(iterations = 10)]
@@ -6684,3 +10737,283 @@ async fn test_remote_git_branches(
 
     assert_eq!(host_branch.as_ref(), "totally-new-branch");
 }
+
+#[gpui::test]
+async fn test_rapid_mute_toggle_uses_latest_intent(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    // Mute then immediately unmute, without letting the first publish request land. Whichever
+    // ack comes back first, the final state on both ends should match the last toggle (unmuted),
+    // not get clobbered by a stale ack from the superseded mute.
+    room_a.update(cx_a, |room, cx| room.toggle_mute(cx));
+    room_a.update(cx_a, |room, cx| room.toggle_mute(cx));
+    executor.run_until_parked();
+
+    room_a.read_with(cx_a, |room, _| assert!(!room.is_muted()));
+    room_b.read_with(cx_b, |room, _| {
+        assert!(!room.remote_participants()[&client_a.user_id().unwrap()].muted);
+    });
+}
+
+#[gpui::test]
+async fn test_participant_open_path(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "file.rs": "" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    room_a
+        .update(cx_a, |room, cx| room.set_location(Some(&project_a), cx))
+        .await
+        .unwrap();
+
+    let open_path = ProjectPath {
+        worktree_id,
+        path: Arc::from(Path::new("file.rs")),
+    };
+    room_a
+        .update(cx_a, |room, cx| {
+            room.set_open_path(Some(open_path.clone()), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let peer_id_a = client_a.peer_id().unwrap();
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.peer_open_path(peer_id_a), Some(open_path.clone()));
+        assert_eq!(
+            room.remote_participants()[&client_a.user_id().unwrap()].open_path,
+            Some(open_path)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_participant_view_anchor(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "file.rs": "" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    room_a
+        .update(cx_a, |room, cx| room.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    room_a
+        .update(cx_a, |room, cx| room.set_location(Some(&project_a), cx))
+        .await
+        .unwrap();
+
+    let open_path = ProjectPath {
+        worktree_id,
+        path: Arc::from(Path::new("file.rs")),
+    };
+    let anchor = ViewAnchor {
+        line: 12,
+        character: 4,
+    };
+    room_a
+        .update(cx_a, |room, cx| {
+            room.set_open_path(Some(open_path.clone()), Some(anchor), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let peer_id_a = client_a.peer_id().unwrap();
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(room.peer_open_anchor(peer_id_a), Some(anchor));
+        assert_eq!(
+            room.remote_participants()[&client_a.user_id().unwrap()].open_anchor,
+            Some(anchor)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_join_succeeds_with_unreachable_media_backend(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    // Simulate the media backend being unreachable for user_a's connection attempt - the room
+    // should still come up as text-only collaboration rather than failing the whole join.
+    server
+        .test_live_kit_server
+        .fail_next_connection_for(client_a.user_id().unwrap().to_string());
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+
+    // `invite` resolves once user_a's room exists, but the LiveKit `connect()` it kicked off is
+    // a detached background task - subscribe now so we see the failure event once it lands,
+    // rather than after `run_until_parked` has already let it run to completion.
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    let events = Rc::new(RefCell::new(Vec::new()));
+    cx_a.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(&room_a, move |_, event, _| events.borrow_mut().push(event.clone()))
+            .detach()
+    });
+
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    assert!(events
+        .borrow()
+        .iter()
+        .any(|event| matches!(event, room::Event::MediaUnavailable { .. })));
+
+    // The backend never came up for this session, so media operations keep failing - but the
+    // room itself (messaging, project sharing, etc.) is unaffected.
+    let share_screen_result = room_a
+        .update(cx_a, |room, cx| room.share_screen(cx))
+        .await;
+    assert!(share_screen_result.is_err());
+}
+
+#[gpui::test]
+async fn test_participant_region(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (client_a, _client_b, _room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    let room_id = room_b.read_with(cx_b, |room, _| room.id());
+    room_b
+        .update(cx_b, |room, cx| {
+            room.apply_room_update_for_test(
+                proto::Room {
+                    id: room_id,
+                    participants: vec![proto::Participant {
+                        user_id: client_a.user_id().unwrap(),
+                        peer_id: client_a.peer_id(),
+                        projects: Vec::new(),
+                        location: None,
+                        participant_index: 0,
+                        role: proto::ChannelRole::Member as i32,
+                        platform: None,
+                        is_observer: false,
+                        mic_state: None,
+                        region: Some("us-east".to_string()),
+                        network_type: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        })
+        .unwrap();
+
+    let peer_id_a = client_a.peer_id().unwrap();
+    room_b.read_with(cx_b, |room, _| {
+        assert_eq!(
+            room.remote_participants()[&client_a.user_id().unwrap()].region,
+            Some("us-east".to_string())
+        );
+        let groups = room.participants_by_region();
+        assert_eq!(
+            groups.get(&Some("us-east".to_string())),
+            Some(&vec![peer_id_a])
+        );
+        assert_eq!(groups.get(&None), None);
+    });
+}
+
+#[gpui::test]
+async fn test_request_leave_confirmation(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let (_client_a, _client_b, room_a, room_b) =
+        join_room_for_test_pair(&mut server, cx_a, cx_b).await;
+
+    // `client_a` placed the call, so they're the host - leaving while `client_b` is still
+    // around requires confirmation.
+    room_a.update(cx_a, |room, cx| {
+        assert!(room.local_participant_is_admin());
+        match room.request_leave(cx) {
+            LeaveConfirmation::NeedsConfirmation(_) => {}
+            LeaveConfirmation::Confirmed(_) => panic!("host leave should need confirmation"),
+        }
+    });
+    room_a.read_with(cx_a, |room, _| {
+        assert!(!room.status().is_offline());
+    });
+
+    // `client_b` is a guest, so they can leave without confirmation.
+    room_b.update(cx_b, |room, cx| {
+        assert!(!room.local_participant_is_admin());
+        match room.request_leave(cx) {
+            LeaveConfirmation::Confirmed(_) => {}
+            LeaveConfirmation::NeedsConfirmation(_) => panic!("guest leave shouldn't need it"),
+        }
+    });
+    executor.run_until_parked();
+    room_b.read_with(cx_b, |room, _| {
+        assert!(room.status().is_offline());
+    });
+
+    // Once `client_b` is gone, the host leaving the now-empty room doesn't need confirmation
+    // either.
+    let confirmation = room_a.update(cx_a, |room, cx| room.request_leave(cx));
+    let leave = match confirmation {
+        LeaveConfirmation::Confirmed(task) => task,
+        LeaveConfirmation::NeedsConfirmation(_) => {
+            panic!("leaving an empty room shouldn't need confirmation")
+        }
+    };
+    leave.await.unwrap();
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.status().is_offline());
+    });
+}
@@ -24,6 +24,7 @@ pub struct TestServer {
     pub api_key: String,
     pub secret_key: String,
     rooms: Mutex<HashMap<String, TestServerRoom>>,
+    failing_connections: Mutex<HashSet<String>>,
     executor: BackgroundExecutor,
 }
 
@@ -41,6 +42,7 @@ impl TestServer {
                 api_key,
                 secret_key,
                 rooms: Default::default(),
+                failing_connections: Default::default(),
                 executor,
             });
             e.insert(server.clone());
@@ -104,6 +106,9 @@ impl TestServer {
 
         let claims = live_kit_server::token::validate(&token, &self.secret_key)?;
         let identity = claims.sub.unwrap().to_string();
+        if self.failing_connections.lock().remove(&identity) {
+            return Err(anyhow!("simulated connection failure for {:?}", identity));
+        }
         let room_name = claims.video.room.unwrap();
         let mut server_rooms = self.rooms.lock();
         let room = (*server_rooms).entry(room_name.to_string()).or_default();
@@ -204,6 +209,12 @@ impl TestServer {
         Ok(())
     }
 
+    /// Makes the next `connect()` attempt from `client_identity` fail, to test a client's
+    /// fallback to text-only collaboration when the media backend is unreachable at join time.
+    pub fn fail_next_connection_for(&self, client_identity: String) {
+        self.failing_connections.lock().insert(client_identity);
+    }
+
     pub async fn disconnect_client(&self, client_identity: String) {
         // todo(linux): Remove this once the cross-platform LiveKit implementation is merged
         #[cfg(any(test, feature = "test-support"))]
@@ -244,6 +244,7 @@ messages!(
     (OpenBufferForSymbol, Background),
     (OpenBufferForSymbolResponse, Background),
     (OpenBufferResponse, Background),
+    (ParticipantLeft, Foreground),
     (PerformRename, Background),
     (PerformRenameResponse, Background),
     (Ping, Foreground),
@@ -305,6 +306,7 @@ messages!(
     (UpdateFollowers, Foreground),
     (UpdateInviteInfo, Foreground),
     (UpdateLanguageServer, Foreground),
+    (UpdateParticipantActivity, Foreground),
     (UpdateParticipantLocation, Foreground),
     (UpdateProject, Foreground),
     (UpdateProjectCollaborator, Foreground),
@@ -315,6 +317,14 @@ messages!(
     (LspExtExpandMacro, Background),
     (LspExtExpandMacroResponse, Background),
     (SetRoomParticipantRole, Foreground),
+    (RemoveRoomParticipant, Foreground),
+    (MuteRoomParticipant, Foreground),
+    (ForceMute, Foreground),
+    (SetParticipantObserverMode, Foreground),
+    (SetProjectAccess, Foreground),
+    (RequestMuteRoomParticipant, Foreground),
+    (RequestMute, Foreground),
+    (SessionSuperseded, Foreground),
     (BlameBuffer, Foreground),
     (BlameBufferResponse, Foreground),
     (RejoinRemoteProjects, Foreground),
@@ -465,6 +475,11 @@ request_messages!(
     (UpdateWorktree, Ack),
     (LspExtExpandMacro, LspExtExpandMacroResponse),
     (SetRoomParticipantRole, Ack),
+    (RemoveRoomParticipant, Ack),
+    (MuteRoomParticipant, Ack),
+    (SetParticipantObserverMode, Ack),
+    (SetProjectAccess, Ack),
+    (RequestMuteRoomParticipant, Ack),
     (BlameBuffer, BlameBufferResponse),
     (RejoinRemoteProjects, RejoinRemoteProjectsResponse),
     (MultiLspQuery, MultiLspQueryResponse),
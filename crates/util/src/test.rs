@@ -1,5 +1,6 @@
 mod assertions;
 mod marked_text;
+mod test_override;
 
 use git2;
 use std::{
@@ -10,6 +11,7 @@ use tempfile::TempDir;
 
 pub use assertions::*;
 pub use marked_text::*;
+pub use test_override::*;
 
 pub fn temp_tree(tree: serde_json::Value) -> TempDir {
     let dir = TempDir::new().unwrap();
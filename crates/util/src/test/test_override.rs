@@ -0,0 +1,39 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const UNSET: u64 = u64::MAX;
+
+/// A process-global override for a tunable constant (a timeout, a threshold, a protocol
+/// version...), so a test can swap in a different value without threading it through every call
+/// site that reads the constant. Store one as a `static`, read it through [`Self::get`] from the
+/// function that normally returns the constant, and expose a `set_..._for_test` wrapper around
+/// [`Self::set`].
+///
+/// Only safe when a single test touches a given override at a time, which holds under
+/// `cargo nextest` (this repo's test runner gives each test its own process) but not under a
+/// bare `cargo test`, which runs tests as threads within one process.
+pub struct TestOverride(AtomicU64);
+
+impl TestOverride {
+    pub const fn new() -> Self {
+        Self(AtomicU64::new(UNSET))
+    }
+
+    /// Sets the override. `value` must not be [`u64::MAX`], which is reserved to mean "unset".
+    pub fn set(&self, value: u64) {
+        debug_assert_ne!(value, UNSET, "u64::MAX is reserved to mean \"unset\"");
+        self.0.store(value, Ordering::SeqCst);
+    }
+
+    pub fn get(&self) -> Option<u64> {
+        match self.0.load(Ordering::SeqCst) {
+            UNSET => None,
+            value => Some(value),
+        }
+    }
+}
+
+impl Default for TestOverride {
+    fn default() -> Self {
+        Self::new()
+    }
+}